@@ -1,3 +1,4 @@
+use crate::utils::format::Format;
 use clap::Parser;
 use std::path::PathBuf;
 
@@ -22,13 +23,196 @@ pub enum Commands {
     },
     /// Display author information
     Author,
+    /// Check that README.md is up to date and lint it
+    Check {
+        /// Project directory
+        #[arg(short, long, default_value = ".")]
+        project_folder: PathBuf,
+    },
+    /// Manage .wtfm.json
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+        /// Manage the defaults of the user in ~/.config/wtfm instead
+        #[arg(long, global = true)]
+        global: bool,
+    },
     /// Generate README file
     Generate {
-        /// Output directory for the generated README
+        /// Output directory for the generated README, or `-` for stdout
         #[arg(short, long, default_value = ".")]
         output: PathBuf,
         /// Project directory
         #[arg(short, long, default_value = ".")]
         project_folder: PathBuf,
+        /// File name of the generated README, relative to the output directory
+        #[arg(short, long)]
+        file: Option<PathBuf>,
+        /// Markup of the generated README
+        #[arg(long, value_enum)]
+        format: Option<Format>,
+        /// Print the generated files to stdout instead of writing them
+        #[arg(long)]
+        dry_run: bool,
+        /// Show a diff of the changes instead of writing them
+        #[arg(long, conflicts_with = "dry_run")]
+        diff: bool,
+        /// Keep running and regenerate when the configuration, a manifest,
+        /// the license or a template changes
+        #[arg(short, long, conflicts_with_all = ["dry_run", "diff"])]
+        watch: bool,
+        /// Also generate CONTRIBUTING.md, CODE_OF_CONDUCT.md and SECURITY.md,
+        /// tracking them in .wtfm.json
+        #[arg(long)]
+        community: bool,
+    },
+    /// Generate a man page for each binary of the project
+    Man {
+        /// Project directory
+        #[arg(short, long, default_value = ".")]
+        project_folder: PathBuf,
+        /// Output directory for the man pages, or `-` for stdout
+        #[arg(short, long, default_value = ".")]
+        output: PathBuf,
+        /// Only document this binary
+        #[arg(short, long)]
+        bin: Option<String>,
+    },
+    /// Create a documentation tree, or issue and pull request templates, for
+    /// the project
+    Scaffold {
+        /// What to create
+        #[arg(value_enum)]
+        layout: Layout,
+        /// Project directory
+        #[arg(short, long, default_value = ".")]
+        project_folder: PathBuf,
+        /// Root of the tree, relative to the project directory
+        #[arg(short, long, default_value = "docs")]
+        root: PathBuf,
+    },
+    /// Preview the README and the docs in the browser, reloading on changes
+    Serve {
+        /// Project directory
+        #[arg(short, long, default_value = ".")]
+        project_folder: PathBuf,
+        /// Port to listen on, on localhost only
+        #[arg(long, default_value_t = 4000)]
+        port: u16,
+    },
+    /// Build a static HTML site from the README and the docs/ directory
+    Site {
+        /// Project directory
+        #[arg(short, long, default_value = ".")]
+        project_folder: PathBuf,
+        /// Output directory, `target/wtfm-site` in the project by default
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// List the built-in README templates or copy one to customize it
+    Templates {
+        #[command(subcommand)]
+        command: TemplatesCommand,
+    },
+    /// Report version sources and suggest the next release
+    Version {
+        /// Project directory
+        #[arg(short, long, default_value = ".")]
+        project_folder: PathBuf,
+        /// Write the next version to Cargo.toml, package.json and .wtfm.json
+        #[arg(short, long)]
+        apply: bool,
+        /// Use this version instead of the suggested one
+        #[arg(short, long)]
+        set: Option<String>,
+    },
+}
+
+#[derive(clap::Subcommand)]
+pub enum ConfigCommand {
+    /// Run the wizard again, proposing the current settings
+    Edit {
+        /// Project directory
+        #[arg(short, long, default_value = ".")]
+        project_folder: PathBuf,
+    },
+    /// Print a setting, e.g. `authors[0].email`
+    Get {
+        /// Dotted path of the setting
+        key: String,
+        /// Project directory
+        #[arg(short, long, default_value = ".")]
+        project_folder: PathBuf,
+    },
+    /// Print every setting with its dotted path
+    List {
+        /// Project directory
+        #[arg(short, long, default_value = ".")]
+        project_folder: PathBuf,
+    },
+    /// Upgrade .wtfm.json to the current schema version
+    Migrate {
+        /// Project directory
+        #[arg(short, long, default_value = ".")]
+        project_folder: PathBuf,
+        /// Print the migrated configuration instead of writing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Print the JSON Schema of .wtfm.json, for editors and CI
+    Schema,
+    /// Change a setting. The value is read as JSON when it parses as such,
+    /// as text otherwise
+    Set {
+        /// Dotted path of the setting
+        key: String,
+        value: String,
+        /// Project directory
+        #[arg(short, long, default_value = ".")]
+        project_folder: PathBuf,
     },
+    /// Remove a setting, or an item of a list
+    Unset {
+        /// Dotted path of the setting
+        key: String,
+        /// Project directory
+        #[arg(short, long, default_value = ".")]
+        project_folder: PathBuf,
+    },
+    /// Check .wtfm.json, reporting errors and unknown keys with their location
+    Validate {
+        /// Project directory
+        #[arg(short, long, default_value = ".")]
+        project_folder: PathBuf,
+    },
+}
+
+#[derive(clap::Subcommand)]
+pub enum TemplatesCommand {
+    /// List the built-in README variants
+    List,
+    /// Copy a built-in variant and the partials it includes into the project
+    Eject {
+        /// Variant to copy, as shown by `wtfm templates list`
+        name: String,
+        /// Project directory
+        #[arg(short, long, default_value = ".")]
+        project_folder: PathBuf,
+        /// Markup of the templates to copy
+        #[arg(long, value_enum, default_value_t = Format::Markdown)]
+        format: Format,
+        /// Destination, relative to the project directory.
+        /// Defaults to `.wtfm/templates/<markup>`
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+/// Layouts `scaffold` can create.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Layout {
+    /// Tutorials, how-to guides, reference and explanation (https://diataxis.fr)
+    Diataxis,
+    /// Issue and pull request templates for the forge of the git remote
+    IssueTemplates,
 }
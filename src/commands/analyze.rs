@@ -1,5 +1,5 @@
 use crate::cli::args::Commands;
-use crate::config::wtfm::WtfmConfig;
+use crate::config::source::ConfigSource;
 use crate::debug;
 use crate::utils::cargo::Cargo;
 use crate::utils::git::Git;
@@ -9,8 +9,7 @@ use std::fs;
 // Analyze the current project and print the results
 // TODO:
 // - Dig deeper into .git area for fetching more informations, maybe via API, detacting if it's GitHub/Gitlab/Bitbucket/...
-// - Add a way to edit the .wtfm.json file
-pub fn execute(cmd: &Commands, debug: bool) -> Result<(), Box<dyn std::error::Error>> {
+pub fn execute(cmd: &Commands, _debug: bool) -> Result<(), Box<dyn std::error::Error>> {
     if let Commands::Analyze { project_folder } = cmd {
         // look into current folder for :
         // - .wtfm.json -> it's a wtfm project (if there's a file, user can edit it)
@@ -26,19 +25,20 @@ pub fn execute(cmd: &Commands, debug: bool) -> Result<(), Box<dyn std::error::Er
         let mut is_license_file = false;
 
         // Usa project_folder per tutti i percorsi
-        let config_path = project_folder.join(".wtfm.json");
         let package_json_path = project_folder.join("package.json");
         let license_path = project_folder.join("LICENSE");
 
-        debug!("Looking for config at: {:?}", config_path);
-        debug!("Config file exists: {}", config_path.exists());
+        // The first source holding a configuration, see ConfigSource::candidates
+        let source = ConfigSource::find(project_folder);
+        debug!("Looking for config at: {}", source);
+        debug!("Config file exists: {}", source.exists());
 
-        let config = WtfmConfig::load(&config_path, debug);
-        debug!("Config loaded: {}", config.is_some());
-
-        if config.is_some() {
-            is_wtfm_project = true;
-            debug!("Found .wtfm.json configuration file");
+        match source.read() {
+            Ok(_) => {
+                is_wtfm_project = true;
+                debug!("Found configuration in {}", source);
+            }
+            Err(e) => debug!("Config not loaded: {}", e),
         }
 
         let git = Git::new(project_folder);
@@ -72,6 +72,9 @@ pub fn execute(cmd: &Commands, debug: bool) -> Result<(), Box<dyn std::error::Er
                 "No".red()
             }
         );
+        if is_wtfm_project {
+            println!("Configuration: {}", source);
+        }
         println!(
             "Git Repository: {}",
             if is_git_repo {
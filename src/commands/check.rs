@@ -0,0 +1,304 @@
+use crate::cli::args::Commands;
+use crate::config::source::ConfigSource;
+use crate::config::wizard::WizardAnswers;
+use crate::config::wtfm::{is_community_document, WtfmConfig};
+use crate::utils::cargo::{Cargo, CargoInfo};
+use crate::utils::format::Format;
+use crate::utils::git::{self, Git, GitInfo};
+use crate::utils::node::{Node, NodeInfo};
+use crate::utils::{diataxis, diff, markdown, regions, template, version};
+use colored::*;
+use std::fs;
+
+// Render the documents from the current configuration, merge each into the
+// committed one as `generate` would and compare the two, then run a few lint
+// rules on the committed Markdown README.
+// Any problem makes the command fail so it can gate CI.
+pub fn execute(cmd: &Commands, _debug: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if let Commands::Check { project_folder } = cmd {
+        let (config, source) =
+            WtfmConfig::find_with_defaults(project_folder).map_err(|e| e.to_string())?;
+
+        println!("{}: {}", "README Check".bold(), "-".repeat(40));
+        let answers = WizardAnswers::from(&config);
+        let mut problems = 0;
+        let mut readme = None;
+
+        for document in config.generated_documents() {
+            let name = document.destination.display().to_string();
+            let committed =
+                fs::read_to_string(project_folder.join(&document.destination)).unwrap_or_default();
+            let rendered = template::render(&answers, &document, project_folder)
+                .map_err(|e| format!("Failed to render {}: {}", name, e))?;
+            // HTML pages are standalone renders with no regions to merge
+            let rendered = if document.format == Format::Html {
+                rendered
+            } else {
+                regions::merge(&committed, &rendered, document.format.markers()).content
+            };
+
+            let generated = format!("{} (generated)", name);
+            match diff::unified(&committed, &rendered, &name, &generated) {
+                Some(unified) => {
+                    problems += 1;
+                    println!("{} {} is out of date", "✗".red(), name);
+                    print!("{}", diff::colorize(&unified));
+                }
+                None => println!("{} {} is up to date", "✓".green(), name),
+            }
+            if readme.is_none()
+                && document.format == Format::Markdown
+                && !is_community_document(&document)
+            {
+                readme = Some(committed);
+            }
+        }
+
+        let cargo = Cargo::new(project_folder);
+        let node = Node::new(project_folder);
+        let git_info = Git::new(project_folder).info();
+        let issues = lint(
+            readme.as_deref(),
+            &config,
+            &source,
+            cargo.info(),
+            node.info(),
+            git_info.as_ref(),
+        );
+        for issue in &issues {
+            println!("{} {}", "✗".red(), issue);
+        }
+        problems += issues.len();
+
+        // Empty quadrants are expected while the documentation grows, so they
+        // are reported without failing the check
+        if let Some(docs) = &config.docs {
+            for quadrant in diataxis::empty_quadrants(&project_folder.join(&docs.root)) {
+                println!(
+                    "{} {} in {:?} has no pages yet",
+                    "!".yellow(),
+                    quadrant.title(),
+                    docs.root.join(quadrant.dir())
+                );
+            }
+        }
+
+        if problems > 0 {
+            return Err(format!("README check failed with {} problem(s)", problems).into());
+        }
+        println!("{} All lint rules passed", "✓".green());
+    }
+    Ok(())
+}
+
+/// Runs every lint rule and returns the problems found. Rules about the
+/// content of the README are skipped when there's no Markdown README.
+pub fn lint(
+    readme: Option<&str>,
+    config: &WtfmConfig,
+    source: &ConfigSource,
+    cargo: Option<&CargoInfo>,
+    node: Option<&NodeInfo>,
+    git: Option<&GitInfo>,
+) -> Vec<String> {
+    let mut issues = Vec::new();
+    issues.extend(check_license(readme, config, cargo));
+    issues.extend(check_version(config, source, git));
+    issues.extend(check_repository(cargo, node, git));
+    issues.extend(
+        readme
+            .map(markdown::empty_sections)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|title| format!("Section '{}' is empty", title)),
+    );
+    issues
+}
+
+// The license declared in Cargo.toml must be the one the README talks about
+fn check_license(
+    readme: Option<&str>,
+    config: &WtfmConfig,
+    cargo: Option<&CargoInfo>,
+) -> Option<String> {
+    let readme = readme?;
+    let expected = cargo?.license.as_deref()?;
+    let Some(section) = markdown::section(readme, "License") else {
+        return Some(format!(
+            "README has no License section but Cargo.toml declares {}",
+            expected
+        ));
+    };
+
+    let mut names = vec![expected.to_string()];
+    if config.license.spdx_id == expected {
+        names.push(config.license.name.clone());
+    }
+    names.extend(
+        crate::licenses::License::get_licenses()
+            .into_iter()
+            .filter(|license| license.spdx_id == expected)
+            .map(|license| license.name),
+    );
+
+    if names.iter().any(|name| section.contains(name.as_str())) {
+        None
+    } else {
+        Some(format!(
+            "License section does not mention {} declared in Cargo.toml",
+            expected
+        ))
+    }
+}
+
+// A version older than the latest tag means the release was never recorded.
+// Being ahead is fine: that's an unreleased version waiting for its tag.
+fn check_version(
+    config: &WtfmConfig,
+    source: &ConfigSource,
+    git: Option<&GitInfo>,
+) -> Option<String> {
+    let tag = version::behind_latest_tag(&config.version, &git?.tags)?;
+    Some(format!(
+        "Version {} in {} is behind the latest git tag {}",
+        config.version, source, tag
+    ))
+}
+
+fn check_repository(
+    cargo: Option<&CargoInfo>,
+    node: Option<&NodeInfo>,
+    git: Option<&GitInfo>,
+) -> Vec<String> {
+    let Some(remote) = git
+        .and_then(|info| info.remote_url.as_deref())
+        .and_then(git::web_url)
+    else {
+        return Vec::new();
+    };
+
+    let declared = [
+        (
+            "Cargo.toml",
+            cargo.and_then(|info| info.repository.as_deref()),
+        ),
+        (
+            "package.json",
+            node.and_then(|info| info.repository.as_deref()),
+        ),
+    ];
+    declared
+        .into_iter()
+        .filter_map(|(source, repository)| {
+            let repository = repository?;
+            if git::web_url(repository).as_deref() == Some(remote.as_str()) {
+                None
+            } else {
+                Some(format!(
+                    "Repository {} in {} does not match git remote {}",
+                    repository, source, remote
+                ))
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::wtfm::Document;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_check_detects_drift() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = WtfmConfig::new_test();
+        config.save(temp_dir.path().join(".wtfm.json")).unwrap();
+        let cmd = Commands::Check {
+            project_folder: temp_dir.path().to_path_buf(),
+        };
+
+        let rendered = template::generate_readme_with_template(&WizardAnswers::from(&config));
+        let readme = regions::merge("", &rendered, Format::Markdown.markers()).content;
+        fs::write(temp_dir.path().join("README.md"), &readme).unwrap();
+        assert!(execute(&cmd, false).is_ok());
+
+        // custom prose is not drift, edits inside a managed region are
+        fs::write(
+            temp_dir.path().join("README.md"),
+            readme.clone() + "\nNotes\n",
+        )
+        .unwrap();
+        assert!(execute(&cmd, false).is_ok());
+
+        let edited = readme.replace("Test Description", "Edited");
+        fs::write(temp_dir.path().join("README.md"), edited).unwrap();
+        assert!(execute(&cmd, false).is_err());
+    }
+
+    #[test]
+    fn test_check_declared_documents() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = WtfmConfig::new_test();
+        config.documents = vec![Document::readme("README.adoc", Format::Asciidoc)];
+        config.save(temp_dir.path().join(".wtfm.json")).unwrap();
+        let cmd = Commands::Check {
+            project_folder: temp_dir.path().to_path_buf(),
+        };
+
+        let document = &config.documents[0];
+        let rendered =
+            template::render(&WizardAnswers::from(&config), document, temp_dir.path()).unwrap();
+        let readme = regions::merge("", &rendered, Format::Asciidoc.markers()).content;
+        fs::write(temp_dir.path().join("README.adoc"), &readme).unwrap();
+        assert!(execute(&cmd, false).is_ok());
+
+        let edited = readme.replace("Test Description", "Edited");
+        fs::write(temp_dir.path().join("README.adoc"), edited).unwrap();
+        assert!(execute(&cmd, false).is_err());
+    }
+
+    #[test]
+    fn test_lint_rules() {
+        let config = WtfmConfig::new_test();
+        let cargo = CargoInfo {
+            package_name: Some("test".to_string()),
+            version: Some("0.1.0".to_string()),
+            authors: Vec::new(),
+            description: None,
+            repository: Some("https://github.com/acme/other".to_string()),
+            license: Some("Apache-2.0".to_string()),
+            keywords: Vec::new(),
+            categories: Vec::new(),
+            bins: Vec::new(),
+            features: Vec::new(),
+            rust_version: None,
+        };
+        let git = GitInfo {
+            is_git_repo: true,
+            current_branch: Some("main".to_string()),
+            remote_url: Some("git@github.com:acme/test.git".to_string()),
+            tags: vec!["v0.2.0".to_string()],
+        };
+        let readme = "# Test\n\n## License\n\nLicensed under MIT License.\n\n## Usage\n";
+
+        let source = ConfigSource::file(Path::new(".wtfm.toml"));
+        let issues = lint(
+            Some(readme),
+            &config,
+            &source,
+            Some(&cargo),
+            None,
+            Some(&git),
+        );
+        assert_eq!(issues.len(), 4);
+        assert!(issues[0].contains("Apache-2.0"));
+        assert_eq!(
+            issues[1],
+            "Version 0.1.0 in .wtfm.toml is behind the latest git tag v0.2.0"
+        );
+        assert!(issues[2].contains("https://github.com/acme/test"));
+        assert!(issues[3].contains("Usage"));
+    }
+}
@@ -0,0 +1,302 @@
+use crate::cli::args::{Commands, ConfigCommand};
+use crate::config::global::{self, GlobalConfig};
+use crate::config::keys::{self, KeyPath};
+use crate::config::migrate::SCHEMA_VERSION;
+use crate::config::schema;
+use crate::config::source::ConfigSource;
+use crate::config::wizard::WizardAnswers;
+use crate::config::wtfm::WtfmConfig;
+use colored::*;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::path::Path;
+
+pub fn execute(cmd: &Commands, _debug: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if let Commands::Config { command, global } = cmd {
+        if *global {
+            let path =
+                global::path().ok_or("No config directory found, set XDG_CONFIG_HOME or HOME")?;
+            return execute_global(command, &path);
+        }
+        match command {
+            ConfigCommand::Edit { project_folder } => {
+                let (mut config, source) = find(project_folder)?;
+                let answers = WizardAnswers::from_interactive_with(&WizardAnswers::from(&config));
+                config.apply_answers(&answers);
+                source.save(&config)?;
+                println!("{} Updated {}", "✓".green(), source);
+            }
+            ConfigCommand::Get {
+                key,
+                project_folder,
+            } => {
+                let (config, _) = find(project_folder)?;
+                get(&serde_json::to_value(&config)?, key)?;
+            }
+            ConfigCommand::List { project_folder } => {
+                let (config, _) = find(project_folder)?;
+                list(&serde_json::to_value(&config)?);
+            }
+            ConfigCommand::Migrate {
+                project_folder,
+                dry_run,
+            } => {
+                let source = ConfigSource::find(project_folder);
+                let (config, applied) = source.read_migrated().map_err(|e| e.to_string())?;
+                if applied.is_empty() {
+                    println!(
+                        "{} {} is already at schema version {}",
+                        "✓".green(),
+                        source,
+                        SCHEMA_VERSION
+                    );
+                    return Ok(());
+                }
+
+                // With --dry-run the configuration goes to stdout, so
+                // the steps go to stderr
+                for step in &applied {
+                    eprintln!("  {} {}", "→".blue(), step);
+                }
+                if *dry_run {
+                    println!("{}", source.render(&config)?.trim_end());
+                } else {
+                    source.save(&config)?;
+                    println!(
+                        "{} Migrated {} to schema version {}",
+                        "✓".green(),
+                        source,
+                        SCHEMA_VERSION
+                    );
+                }
+            }
+            ConfigCommand::Schema => {
+                println!("{}", serde_json::to_string_pretty(&schema::schema())?);
+            }
+            ConfigCommand::Set {
+                key,
+                value,
+                project_folder,
+            } => {
+                let key = KeyPath::parse(key)?;
+                let (config, source) = find(project_folder)?;
+                let updated: WtfmConfig = set(&serde_json::to_value(&config)?, &key, value)?;
+                source.save(&updated)?;
+                println!("{} {} updated in {}", "✓".green(), key, source);
+            }
+            ConfigCommand::Unset {
+                key,
+                project_folder,
+            } => {
+                let key = KeyPath::parse(key)?;
+                let (config, source) = find(project_folder)?;
+                let updated: WtfmConfig = unset(&serde_json::to_value(&config)?, &key)?;
+                source.save(&updated)?;
+                println!("{} {} removed from {}", "✓".green(), key, source);
+            }
+            ConfigCommand::Validate { project_folder } => {
+                let source = ConfigSource::find(project_folder);
+                let warnings = schema::validate(&source).map_err(|e| e.to_string())?;
+                for warning in &warnings {
+                    println!("{} {}: {}", "⚠".yellow(), source, warning);
+                }
+                println!("{} {} is valid", "✓".green(), source);
+            }
+        }
+    }
+    Ok(())
+}
+
+// The subcommands with --global, on the defaults of the user at `path`
+fn execute_global(command: &ConfigCommand, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let defaults = GlobalConfig::read(path).map_err(|e| e.to_string())?;
+    match command {
+        ConfigCommand::Edit { .. } => {
+            defaults.from_interactive().save(path)?;
+            println!("{} Updated {}", "✓".green(), path.display());
+        }
+        ConfigCommand::Get { key, .. } => get(&serde_json::to_value(&defaults)?, key)?,
+        ConfigCommand::List { .. } => list(&serde_json::to_value(&defaults)?),
+        ConfigCommand::Set { key, value, .. } => {
+            let key = KeyPath::parse(key)?;
+            let updated: GlobalConfig = set(&serde_json::to_value(&defaults)?, &key, value)?;
+            updated
+                .check()
+                .map_err(|e| format!("Invalid value for `{}`: {}", key, e))?;
+            updated.save(path)?;
+            println!("{} {} updated in {}", "✓".green(), key, path.display());
+        }
+        ConfigCommand::Unset { key, .. } => {
+            let key = KeyPath::parse(key)?;
+            let updated: GlobalConfig = unset(&serde_json::to_value(&defaults)?, &key)?;
+            updated.save(path)?;
+            println!("{} {} removed from {}", "✓".green(), key, path.display());
+        }
+        ConfigCommand::Migrate { .. } | ConfigCommand::Schema | ConfigCommand::Validate { .. } => {
+            return Err("This command applies to the project configuration, not --global".into());
+        }
+    }
+    Ok(())
+}
+
+fn get(settings: &Value, key: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let key = KeyPath::parse(key)?;
+    match key.get(settings) {
+        Some(Value::String(text)) => println!("{}", text),
+        Some(value) => println!("{}", serde_json::to_string_pretty(value)?),
+        None => return Err(format!("`{}` is not set", key).into()),
+    }
+    Ok(())
+}
+
+fn list(settings: &Value) {
+    for (key, value) in keys::flatten(settings) {
+        println!("{} = {}", key.cyan(), value);
+    }
+}
+
+// The settings with `key` set to `raw`, typed when that keeps them valid
+fn set<T: DeserializeOwned>(current: &Value, key: &KeyPath, raw: &str) -> Result<T, String> {
+    // `123` may well be meant as text, like a project name
+    let typed = keys::parse_value(raw);
+    match with(current, key, typed.clone()) {
+        Err(e) if !typed.is_string() => {
+            with(current, key, Value::String(raw.to_string())).map_err(|_| e)
+        }
+        result => result,
+    }
+}
+
+fn unset<T: DeserializeOwned>(current: &Value, key: &KeyPath) -> Result<T, String> {
+    let mut value = current.clone();
+    if key.unset(&mut value).is_none() {
+        return Err(format!("`{}` is not set", key));
+    }
+    serde_json::from_value(value).map_err(|e| format!("`{}` can't be removed: {}", key, e))
+}
+
+fn find(project_folder: &Path) -> Result<(WtfmConfig, ConfigSource), String> {
+    WtfmConfig::find(project_folder).map_err(|e| e.to_string())
+}
+
+// The settings with `key` set to `value`, if they're still valid
+fn with<T: DeserializeOwned>(current: &Value, key: &KeyPath, value: Value) -> Result<T, String> {
+    let mut updated = current.clone();
+    key.set(&mut updated, value)?;
+    serde_json::from_value(updated).map_err(|e| format!("Invalid value for `{}`: {}", key, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn config(command: ConfigCommand) -> Result<(), Box<dyn std::error::Error>> {
+        execute(
+            &Commands::Config {
+                command,
+                global: false,
+            },
+            false,
+        )
+    }
+
+    #[test]
+    fn test_set_and_unset() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_folder = temp_dir.path().to_path_buf();
+        fs::write(
+            project_folder.join(".wtfm.toml"),
+            "schema_version = 2\nproject_name = \"Demo\"\ndescription = \"\"\nversion = \"0.1.0\"\nlicense = \"MIT\"\nsetup_ci = false\nauthor_quantity = 1\nauthors = [{ name = \"Jane\", email = \"\" }]\n",
+        )
+        .unwrap();
+        let set = |key: &str, value: &str| {
+            config(ConfigCommand::Set {
+                key: key.to_string(),
+                value: value.to_string(),
+                project_folder: project_folder.clone(),
+            })
+        };
+
+        set("authors[0].email", "jane@example.com").unwrap();
+        set("project_name", "1984").unwrap();
+        set("setup_ci", "true").unwrap();
+        set("features[0]", "Fast").unwrap();
+        let error = set("version", "one").unwrap_err().to_string();
+        assert!(
+            error.starts_with("Invalid value for `version`"),
+            "{}",
+            error
+        );
+        assert!(set("setup_ci", "maybe").is_err());
+
+        let (saved, source) = WtfmConfig::find(&project_folder).unwrap();
+        assert_eq!(source.path, project_folder.join(".wtfm.toml"));
+        assert_eq!(saved.authors[0].email, "jane@example.com");
+        assert_eq!(saved.project_name, "1984");
+        assert!(saved.setup_ci);
+        assert_eq!(saved.content.features, vec!["Fast"]);
+
+        let unset = |key: &str| {
+            config(ConfigCommand::Unset {
+                key: key.to_string(),
+                project_folder: project_folder.clone(),
+            })
+        };
+        unset("features").unwrap();
+        assert!(unset("features").is_err());
+        assert!(unset("project_name").is_err());
+        let (saved, _) = WtfmConfig::find(&project_folder).unwrap();
+        assert!(saved.content.features.is_empty());
+        assert_eq!(saved.project_name, "1984");
+    }
+
+    #[test]
+    fn test_global_set_and_unset() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("wtfm").join("config.json");
+        let global = |command| execute_global(&command, &path);
+        let project_folder = temp_dir.path().to_path_buf();
+
+        global(ConfigCommand::Set {
+            key: "authors[0]".to_string(),
+            value: r#"{ "name": "Jane", "email": "jane@example.com" }"#.to_string(),
+            project_folder: project_folder.clone(),
+        })
+        .unwrap();
+        global(ConfigCommand::Set {
+            key: "license".to_string(),
+            value: "Apache-2.0".to_string(),
+            project_folder: project_folder.clone(),
+        })
+        .unwrap();
+        assert!(global(ConfigCommand::Set {
+            key: "setup_ci".to_string(),
+            value: "maybe".to_string(),
+            project_folder: project_folder.clone(),
+        })
+        .is_err());
+        assert!(global(ConfigCommand::Set {
+            key: "license".to_string(),
+            value: "MIT OR".to_string(),
+            project_folder: project_folder.clone(),
+        })
+        .is_err());
+        assert!(global(ConfigCommand::Validate {
+            project_folder: project_folder.clone()
+        })
+        .is_err());
+
+        let defaults = GlobalConfig::read(&path).unwrap();
+        assert_eq!(defaults.authors[0].email, "jane@example.com");
+        assert_eq!(defaults.license.as_deref(), Some("Apache-2.0"));
+
+        global(ConfigCommand::Unset {
+            key: "license".to_string(),
+            project_folder,
+        })
+        .unwrap();
+        assert_eq!(GlobalConfig::read(&path).unwrap().license, None);
+    }
+}
@@ -1,74 +1,325 @@
 use crate::cli::args::Commands;
+use crate::config::global::GlobalConfig;
+use crate::config::migrate::SCHEMA_VERSION;
+use crate::config::source::{self, ConfigSource};
 use crate::config::wizard::WizardAnswers;
-use crate::config::wtfm::{Author, WtfmConfig};
+use crate::config::wtfm::{is_community_document, Author, ConfigError, Document, WtfmConfig};
+use crate::utils::cargo::Cargo;
+use crate::utils::format::Format;
 use crate::utils::git::Git;
+use crate::utils::template::Detected;
+use crate::utils::watch::Watcher;
+use crate::utils::{diff, regions, template, version};
+use colored::*;
 use std::fs;
+use std::path::{Path, PathBuf};
 
 pub fn execute(cmd: &Commands, debug: bool) -> Result<(), Box<dyn std::error::Error>> {
     if let Commands::Generate {
         output,
+        watch: true,
+        ..
+    } = cmd
+    {
+        if output.as_os_str() == "-" {
+            return Err("--watch writes the documents, it can't be used with `--output -`".into());
+        }
+    }
+    generate(cmd, debug)?;
+    if let Commands::Generate {
         project_folder,
+        watch: true,
+        ..
     } = cmd
     {
-        let config_path = project_folder.join(".wtfm.json");
-        let config = WtfmConfig::load(&config_path, debug);
+        watch(cmd, project_folder, debug)?;
+    }
+    Ok(())
+}
+
+// Regenerate whenever the configuration, a manifest, the license or a
+// template changes. Failures are reported and the watch goes on, so a
+// half-written template doesn't end the session.
+fn watch(
+    cmd: &Commands,
+    project_folder: &Path,
+    debug: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (files, dirs) = watched(project_folder);
+    let watcher = Watcher::new(&files, &dirs)?;
+
+    println!("Watching for changes, press Ctrl-C to stop");
+    while let Some(changed) = watcher.wait() {
+        let names: Vec<String> = changed
+            .iter()
+            .map(|path| {
+                path.strip_prefix(project_folder.canonicalize().unwrap_or_default())
+                    .unwrap_or(path)
+                    .display()
+                    .to_string()
+            })
+            .collect();
+        println!("\n{} {}", "Changed:".bold(), names.join(", "));
+        // A broken configuration must not bring the wizard back
+        if let Err(e) = WtfmConfig::find(project_folder) {
+            eprintln!("{} {}", "✗".red(), e);
+            continue;
+        }
+        if let Err(e) = generate(cmd, debug) {
+            eprintln!("{} {}", "✗".red(), e);
+        }
+    }
+    Ok(())
+}
+
+// The files and template directories `watch` follows
+fn watched(project_folder: &Path) -> (Vec<PathBuf>, Vec<PathBuf>) {
+    let files = source::FILES
+        .iter()
+        .chain(["LICENSE"].iter())
+        .map(|file| project_folder.join(file))
+        .collect();
+    let dirs = match WtfmConfig::find_with_defaults(project_folder) {
+        Ok((config, _)) => config
+            .template_dirs()
+            .into_iter()
+            .map(|dir| project_folder.join(dir))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    (files, dirs)
+}
+
+fn generate(cmd: &Commands, _debug: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if let Commands::Generate {
+        output,
+        project_folder,
+        file,
+        format,
+        dry_run,
+        diff,
+        community,
+        ..
+    } = cmd
+    {
+        // `--output -` streams the documents to stdout for piping
+        let to_stdout = output.as_os_str() == "-";
+
+        // When stdout carries the rendered files, messages go to stderr
+        let report = |message: String| {
+            if *dry_run || to_stdout {
+                eprintln!("{}", message);
+            } else {
+                println!("{}", message);
+            }
+        };
+
+        let defaults = GlobalConfig::load().map_err(|e| e.to_string())?;
+        let source = ConfigSource::find(project_folder);
+        // Only a missing configuration starts the wizard, an invalid one
+        // would be overwritten
+        let stored = match source.read() {
+            Ok(config) => Some(config),
+            Err(ConfigError::Missing(_)) => None,
+            Err(e) => return Err(e.to_string().into()),
+        };
+        // Rendered with the defaults of the user, saved without them
+        let config = match stored {
+            Some(_) => Some(source.read_with(&defaults).map_err(|e| e.to_string())?),
+            None => None,
+        };
 
         let git = Git::new(project_folder);
 
-        let answers = match config {
+        let (answers, mut config, mut saved) = match config {
             None => {
-                let answers = WizardAnswers::from_interactive();
-                let config = WtfmConfig {
+                let mut proposed = defaults.wizard_defaults(WizardAnswers::default());
+                // Without defaults of the user, the authors of the manifest
+                if proposed.authors.is_empty() {
+                    if let Some(info) = Cargo::new(project_folder).info() {
+                        proposed.authors = info.authors.iter().map(|a| Author::parse(a)).collect();
+                        proposed.author_quantity = proposed.authors.len().max(1) as u32;
+                    }
+                }
+                let answers = WizardAnswers::from_interactive_with(&proposed);
+                // The template the user prefers is left out of the file, it's
+                // merged in again on every run
+                let created = |template: Option<String>| WtfmConfig {
+                    schema: None,
+                    schema_version: SCHEMA_VERSION,
                     project_name: answers.project_name.clone(),
                     description: answers.description.clone(),
                     version: answers.version.clone(),
                     license: answers.license.clone(),
                     setup_ci: answers.setup_ci,
                     author_quantity: answers.author_quantity,
-                    authors: answers
-                        .authors
-                        .iter()
-                        .map(|author| {
-                            let parts: Vec<&str> = author.split('<').collect();
-                            let name = parts[0].trim().to_string();
-                            let email = parts[1]
-                                .trim_matches(|c| c == '<' || c == '>' || c == ' ')
-                                .to_string();
-                            Author { name, email }
-                        })
-                        .collect(),
+                    authors: answers.authors.clone(),
+                    content: answers.content.clone(),
                     git_info: None,
                     cargo_info: None,
+                    template_dir: None,
+                    template,
+                    documents: Vec::new(),
+                    options: Vec::new(),
+                    docs: None,
                 };
-                config.save(&config_path)?;
-                answers
+                let (config, saved) = (created(defaults.template.clone()), created(None));
+                (answers, config, Some(saved))
             }
-            Some(config) => WizardAnswers {
-                project_name: config.project_name,
-                description: config.description,
-                version: config.version,
-                license: config.license,
-                setup_ci: config.setup_ci,
-                author_quantity: config.author_quantity,
-                authors: config
-                    .authors
-                    .iter()
-                    .map(|a| format!("{} <{}>", a.name, a.email))
-                    .collect(),
-            },
+            Some(config) => (WizardAnswers::from(&config), config, None),
+        };
+        // The README of this run, unless documents are declared
+        let readme_format = format.unwrap_or_default();
+        let readme_file = file
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(readme_format.default_file()));
+        if *community && config.track_community_documents(&readme_file, readme_format) {
+            report(format!(
+                "Community health files are now tracked in {}",
+                source
+            ));
+            if let Some(mut written) = saved.take().or(stored) {
+                written.track_community_documents(&readme_file, readme_format);
+                saved = Some(written);
+            }
+        }
+        let new_config = match &saved {
+            Some(config) => Some(source.render(config)?),
+            None => None,
         };
+        let readme = config.readme("", Format::default());
+        let configured_documents = config.documents;
 
-        if !git.is_repo() {
-            println!("Note: Current directory is not a git repository");
+        match git.info() {
+            None => report("Note: Current directory is not a git repository".to_string()),
+            Some(git_info) => {
+                if let Some(tag) = version::behind_latest_tag(&answers.version, &git_info.tags) {
+                    report(format!(
+                        "Warning: version {} is behind the latest git tag {}",
+                        answers.version, tag
+                    ));
+                }
+            }
+        }
+
+        // An explicit --file or --format wins over the documents declared in
+        // .wtfm.json
+        let documents = if file.is_some() || format.is_some() || configured_documents.is_empty() {
+            let mut documents = vec![Document {
+                destination: readme_file,
+                format: readme_format,
+                ..readme
+            }];
+            if *community {
+                documents.extend(
+                    configured_documents
+                        .into_iter()
+                        .filter(is_community_document),
+                );
+            }
+            documents
+        } else {
+            configured_documents
+        };
+
+        let base = if to_stdout { project_folder } else { output };
+        let mut files = Vec::new();
+        for document in &documents {
+            let path = base.join(&document.destination);
+
+            // Only the wtfm-managed regions are replaced, anything else the
+            // user wrote in the document is carried over as is
+            let existing = fs::read_to_string(&path).unwrap_or_default();
+            let rendered = template::render(&answers, document, project_folder).map_err(|e| {
+                format!(
+                    "Failed to render {} for {}: {}",
+                    document.template,
+                    document.destination.display(),
+                    e
+                )
+            })?;
+
+            let name = document.destination.display();
+            // HTML pages are standalone renders with no regions to merge
+            if document.format == Format::Html {
+                if existing != rendered {
+                    report(format!("{}: page updated", name));
+                }
+                files.push((path, rendered));
+                continue;
+            }
+
+            let merged = regions::merge(&existing, &rendered, document.format.markers());
+            if merged.changes.is_empty() {
+                report(format!("{} sections are already up to date", name));
+            }
+            for change in &merged.changes {
+                report(format!("{}: section {} {}", name, change.name, change.kind));
+            }
+            files.push((path, merged.content));
         }
 
-        fs::create_dir_all(output)?;
-        let readme_path = output.join("README.md");
+        // The CI configuration is created once, from then on it's the
+        // user's to edit
+        if answers.setup_ci && !to_stdout {
+            match Detected::detect(&answers, project_folder).pipeline {
+                None => report(
+                    "No Cargo.toml, package.json or Python project found, no CI configuration generated"
+                        .to_string(),
+                ),
+                Some(pipeline) => {
+                    let name = pipeline.system.path();
+                    let path = project_folder.join(name);
+                    if path.exists() {
+                        report(format!("{} already exists, kept as is", name));
+                    } else {
+                        let rendered = pipeline
+                            .render()
+                            .map_err(|e| format!("Failed to render {}: {}", name, e))?;
+                        report(format!("{}: {} pipeline created", name, pipeline.system.title()));
+                        files.push((path, rendered));
+                    }
+                }
+            }
+        }
 
-        let readme_content = crate::utils::template::generate_readme_with_template(&answers);
-        fs::write(&readme_path, &readme_content)?;
+        if to_stdout && !*diff && !*dry_run {
+            if let Some(content) = &new_config {
+                fs::write(&source.path, content)?;
+            }
+        } else if let Some(content) = new_config {
+            files.insert(0, (source.path, content));
+        }
 
-        println!("README successfully generated at {:?}", readme_path);
+        if *dry_run || (to_stdout && !*diff) {
+            for (path, content) in &files {
+                if files.len() > 1 {
+                    println!("==> {} <==", path.display());
+                }
+                print!("{}", content);
+            }
+        } else if *diff {
+            let mut unchanged = true;
+            for (path, content) in &files {
+                let current = fs::read_to_string(path).unwrap_or_default();
+                let name = path.display().to_string();
+                if let Some(unified) = diff::unified(&current, content, &name, &name) {
+                    unchanged = false;
+                    print!("{}", diff::colorize(&unified));
+                }
+            }
+            if unchanged {
+                println!("No changes");
+            }
+        } else {
+            for (path, content) in &files {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(path, content)?;
+                println!("Generated {:?}", path);
+            }
+        }
     }
     Ok(())
 }
@@ -76,41 +327,32 @@ pub fn execute(cmd: &Commands, debug: bool) -> Result<(), Box<dyn std::error::Er
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::licenses::{License, LicenseCategory};
+    use crate::cli::args::TemplatesCommand;
     use tempfile::TempDir;
 
+    // `wtfm generate` in `project_folder`, every flag off
+    fn generate_in(project_folder: &Path) -> Commands {
+        Commands::Generate {
+            output: project_folder.to_path_buf(),
+            project_folder: project_folder.to_path_buf(),
+            file: None,
+            format: None,
+            dry_run: false,
+            diff: false,
+            watch: false,
+            community: false,
+        }
+    }
+
     #[test]
     fn test_generate_readme() {
         let temp_dir = TempDir::new().unwrap();
-        let config = WtfmConfig {
-            project_name: "Test Project".to_string(),
-            description: "Test Description".to_string(),
-            version: "0.1.0".to_string(),
-            license: License::new(
-                "MIT",
-                "MIT License",
-                LicenseCategory::Permissive,
-                true,
-                true,
-                false,
-            ),
-            setup_ci: false,
-            author_quantity: 1,
-            authors: vec![Author {
-                name: "Test Author".to_string(),
-                email: "test@example.com".to_string(),
-            }],
-            git_info: None,
-            cargo_info: None,
-        };
+        let config = WtfmConfig::new_test();
 
         let config_path = temp_dir.path().join(".wtfm.json");
         config.save(&config_path).unwrap();
 
-        let cmd = Commands::Generate {
-            output: temp_dir.path().to_path_buf(),
-            project_folder: temp_dir.path().to_path_buf(),
-        };
+        let cmd = generate_in(temp_dir.path());
 
         execute(&cmd, false).unwrap();
 
@@ -120,39 +362,224 @@ mod tests {
     #[test]
     fn test_generate_readme_with_ci() {
         let temp_dir = TempDir::new().unwrap();
-        let cmd = Commands::Generate {
-            output: temp_dir.path().to_path_buf(),
-            project_folder: temp_dir.path().to_path_buf(),
-        };
+        let cmd = generate_in(temp_dir.path());
 
         let config = WtfmConfig {
-            project_name: "Test Project".to_string(),
-            description: "Test Description".to_string(),
-            version: "0.1.0".to_string(),
-            license: License::new(
-                "MIT",
-                "MIT License",
-                LicenseCategory::Permissive,
-                true,
-                true,
-                false,
-            ),
             setup_ci: true,
-            author_quantity: 1,
-            authors: vec![Author {
-                name: "Test Author".to_string(),
-                email: "test@example.com".to_string(),
-            }],
-            git_info: None,
-            cargo_info: None,
+            ..WtfmConfig::new_test()
         };
 
         let config_path = temp_dir.path().join(".wtfm.json");
         config.save(&config_path).unwrap();
 
+        fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            "[package]\nname = \"test\"\nversion = \"0.1.0\"\nrust-version = \"1.75\"\n",
+        )
+        .unwrap();
+
         execute(&cmd, false).unwrap();
 
         let readme_content = std::fs::read_to_string(temp_dir.path().join("README.md")).unwrap();
         assert!(readme_content.contains("Continuous Integration"));
+        assert!(readme_content.contains("- **Tests** (Rust stable, 1.75): `cargo test`"));
+        let workflow_path = temp_dir.path().join(".github/workflows/ci.yml");
+        let workflow = fs::read_to_string(&workflow_path).unwrap();
+        assert!(workflow.contains("cargo fmt --all -- --check"));
+
+        // An edited workflow is never overwritten
+        fs::write(&workflow_path, "name: Custom\n").unwrap();
+        execute(&cmd, false).unwrap();
+        assert_eq!(
+            fs::read_to_string(&workflow_path).unwrap(),
+            "name: Custom\n"
+        );
+    }
+
+    #[test]
+    fn test_generate_preserves_custom_sections() {
+        let temp_dir = TempDir::new().unwrap();
+        let cmd = generate_in(temp_dir.path());
+        let readme_path = temp_dir.path().join("README.md");
+        fs::write(&readme_path, "# Hand-written\n\nKeep me\n").unwrap();
+
+        let mut config = WtfmConfig::new_test();
+        let config_path = temp_dir.path().join(".wtfm.json");
+        config.save(&config_path).unwrap();
+        execute(&cmd, false).unwrap();
+
+        config.description = "New Description".to_string();
+        config.save(&config_path).unwrap();
+        execute(&cmd, false).unwrap();
+
+        let readme_content = fs::read_to_string(&readme_path).unwrap();
+        assert!(readme_content.contains("Keep me"));
+        assert!(readme_content.contains("New Description"));
+        assert!(!readme_content.contains("Test Description"));
+    }
+
+    #[test]
+    fn test_generate_preview_does_not_write() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = WtfmConfig::new_test();
+        config.save(temp_dir.path().join(".wtfm.json")).unwrap();
+
+        for (dry_run, diff) in [(true, false), (false, true)] {
+            let mut cmd = generate_in(temp_dir.path());
+            if let Commands::Generate {
+                dry_run: preview,
+                diff: show_diff,
+                ..
+            } = &mut cmd
+            {
+                (*preview, *show_diff) = (dry_run, diff);
+            }
+            execute(&cmd, false).unwrap();
+            assert!(!temp_dir.path().join("README.md").exists());
+        }
+    }
+
+    #[test]
+    fn test_generate_dry_run_to_stdout_keeps_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join(".wtfm.json");
+        WtfmConfig::new_test().save(&config_path).unwrap();
+        let saved = fs::read_to_string(&config_path).unwrap();
+
+        // Tracking the community files would otherwise update the config
+        let mut cmd = generate_in(temp_dir.path());
+        if let Commands::Generate {
+            output,
+            dry_run,
+            community,
+            ..
+        } = &mut cmd
+        {
+            (*output, *dry_run, *community) = (PathBuf::from("-"), true, true);
+        }
+        execute(&cmd, false).unwrap();
+        assert_eq!(fs::read_to_string(&config_path).unwrap(), saved);
+        assert!(!temp_dir.path().join("README.md").exists());
+    }
+
+    #[test]
+    fn test_generate_configured_documents() {
+        let temp_dir = TempDir::new().unwrap();
+        let template_dir = temp_dir.path().join("doc-templates");
+        fs::create_dir_all(&template_dir).unwrap();
+        fs::write(
+            template_dir.join("index.tera"),
+            "{% filter managed(name=\"title\") %}# {{ project_name }} docs{% endfilter %}",
+        )
+        .unwrap();
+
+        let config = WtfmConfig {
+            documents: vec![
+                Document::readme("README.en.md", Format::Markdown),
+                Document {
+                    destination: "docs/index.md".into(),
+                    template_dir: Some("doc-templates".into()),
+                    template: "index.tera".to_string(),
+                    format: Format::Markdown,
+                },
+            ],
+            ..WtfmConfig::new_test()
+        };
+        config.save(temp_dir.path().join(".wtfm.json")).unwrap();
+
+        let cmd = generate_in(temp_dir.path());
+        execute(&cmd, false).unwrap();
+
+        assert!(!temp_dir.path().join("README.md").exists());
+        assert!(temp_dir.path().join("README.en.md").exists());
+        let index = fs::read_to_string(temp_dir.path().join("docs/index.md")).unwrap();
+        assert!(index.contains("# Test Project docs"));
+    }
+
+    #[test]
+    fn test_generate_community_files() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            "[package]\nname = \"test\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        let config = WtfmConfig {
+            version: semver::Version::new(1, 2, 0),
+            ..WtfmConfig::new_test()
+        };
+        let config_path = temp_dir.path().join(".wtfm.json");
+        config.save(&config_path).unwrap();
+
+        let mut cmd = generate_in(temp_dir.path());
+        if let Commands::Generate { community, .. } = &mut cmd {
+            *community = true;
+        }
+        execute(&cmd, false).unwrap();
+
+        assert!(temp_dir.path().join("README.md").exists());
+        let contributing = fs::read_to_string(temp_dir.path().join("CONTRIBUTING.md")).unwrap();
+        assert!(contributing.contains("# Contributing to Test Project"));
+        assert!(contributing.contains("```sh\ncargo build\n```"));
+        assert!(contributing.contains("cargo clippy --all-targets -- -D warnings"));
+        let conduct = fs::read_to_string(temp_dir.path().join("CODE_OF_CONDUCT.md")).unwrap();
+        assert!(conduct.contains("enforcement at\ntest@example.com."));
+        let security = fs::read_to_string(temp_dir.path().join("SECURITY.md")).unwrap();
+        assert!(security.contains("| 1.2.x | :white_check_mark: |"));
+        assert!(security.contains("| < 1.2 | :x: |"));
+
+        // Tracked, so a plain run keeps them up to date
+        let config = WtfmConfig::load(&config_path, false).unwrap();
+        assert_eq!(config.documents.len(), 4);
+        fs::remove_file(temp_dir.path().join("SECURITY.md")).unwrap();
+        let cmd = generate_in(temp_dir.path());
+        execute(&cmd, false).unwrap();
+        assert!(temp_dir.path().join("SECURITY.md").exists());
+    }
+
+    #[test]
+    fn test_watch_rebuilds_after_ejected_template_change() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        WtfmConfig::new_test()
+            .save(root.join(".wtfm.json"))
+            .unwrap();
+        let eject = Commands::Templates {
+            command: TemplatesCommand::Eject {
+                name: "minimal".to_string(),
+                project_folder: root.clone(),
+                format: Format::Markdown,
+                output: None,
+            },
+        };
+        crate::commands::templates::execute(&eject, false).unwrap();
+
+        let (files, dirs) = watched(&root);
+        assert!(dirs.contains(&root.join(".wtfm/templates/markdown")));
+        let watcher = Watcher::new(&files, &dirs).unwrap();
+        let template = root.join(".wtfm/templates/markdown/header.tera");
+        fs::write(&template, "# {{ project_name }}\n\nEdited by hand\n").unwrap();
+        assert!(watcher.wait().unwrap().contains(&template));
+
+        generate(&generate_in(&root), false).unwrap();
+        let readme = fs::read_to_string(root.join("README.md")).unwrap();
+        assert!(readme.contains("Edited by hand"), "{}", readme);
+    }
+
+    #[test]
+    fn test_watch_rejects_stdout_before_rendering() {
+        let temp_dir = TempDir::new().unwrap();
+        // Rendering would fail on this configuration with another error
+        fs::write(temp_dir.path().join(".wtfm.json"), "{").unwrap();
+        let mut cmd = generate_in(temp_dir.path());
+        if let Commands::Generate { output, watch, .. } = &mut cmd {
+            (*output, *watch) = (PathBuf::from("-"), true);
+        }
+        let error = execute(&cmd, false).unwrap_err().to_string();
+        assert!(
+            error.starts_with("--watch writes the documents"),
+            "{}",
+            error
+        );
     }
 }
@@ -0,0 +1,159 @@
+use crate::cli::args::Commands;
+use crate::config::wtfm::{Author, CliOption, WtfmConfig};
+use crate::debug;
+use crate::utils::cargo::Cargo;
+use crate::utils::man::{self, ManPage};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+// Build a roff man page for every binary of the project.
+// Options come from the list declared in .wtfm.json or, when there's none,
+// from the --help output of an already built binary.
+pub fn execute(cmd: &Commands, _debug: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if let Commands::Man {
+        project_folder,
+        output,
+        bin,
+    } = cmd
+    {
+        let (config, _) =
+            WtfmConfig::find_with_defaults(project_folder).map_err(|e| e.to_string())?;
+        let cargo = Cargo::new(project_folder);
+
+        let names = match bin {
+            Some(bin) => vec![bin.clone()],
+            None => {
+                let bins = cargo
+                    .info()
+                    .map(|info| info.bins.clone())
+                    .unwrap_or_default();
+                if bins.is_empty() {
+                    vec![command_name(&config.project_name)]
+                } else {
+                    bins
+                }
+            }
+        };
+
+        let to_stdout = output.as_os_str() == "-";
+        for name in names {
+            let (usage, options) = if config.options.is_empty() {
+                options_from_help(project_folder, &name)
+            } else {
+                debug!("Using the options declared in .wtfm.json");
+                (None, config.options.clone())
+            };
+
+            let summary = cargo
+                .info()
+                .and_then(|info| info.description.clone())
+                .unwrap_or_else(|| config.description.clone());
+            let page = ManPage {
+                name: name.clone(),
+                version: config.version.to_string(),
+                summary,
+                description: config.description.clone(),
+                usage,
+                options,
+                authors: config.authors.iter().map(Author::to_string).collect(),
+                license: config.license.name.clone(),
+            };
+
+            if to_stdout {
+                print!("{}", page.to_roff());
+            } else {
+                fs::create_dir_all(output)?;
+                let page_path = output.join(format!("{}.1", name));
+                fs::write(&page_path, page.to_roff())?;
+                println!("Man page successfully generated at {:?}", page_path);
+            }
+        }
+    }
+    Ok(())
+}
+
+// Projects without binary targets get a page named after the project
+fn command_name(project_name: &str) -> String {
+    project_name
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase()
+}
+
+fn options_from_help(project_folder: &Path, name: &str) -> (Option<String>, Vec<CliOption>) {
+    let executable = format!("{}{}", name, std::env::consts::EXE_SUFFIX);
+    let built = ["release", "debug"]
+        .iter()
+        .map(|profile| {
+            project_folder
+                .join("target")
+                .join(profile)
+                .join(&executable)
+        })
+        .find(|path| path.is_file());
+
+    let Some(binary) = built else {
+        debug!("No built binary found for {}, options left out", name);
+        return (None, Vec::new());
+    };
+
+    match Command::new(&binary).arg("--help").output() {
+        Ok(output) if output.status.success() => {
+            debug!("Reading options from {:?} --help", binary);
+            man::parse_help(&String::from_utf8_lossy(&output.stdout))
+        }
+        _ => {
+            debug!("Failed to run {:?} --help", binary);
+            (None, Vec::new())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_man_page_from_declared_options() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n\n[[bin]]\nname = \"demo-cli\"\npath = \"src/cli.rs\"\n",
+        )
+        .unwrap();
+        let config = WtfmConfig {
+            project_name: "Demo".to_string(),
+            description: "A demo project".to_string(),
+            options: vec![CliOption {
+                flags: "-q, --quiet".to_string(),
+                description: "Print nothing".to_string(),
+            }],
+            ..WtfmConfig::new_test()
+        };
+        config.save(temp_dir.path().join(".wtfm.json")).unwrap();
+
+        let cmd = Commands::Man {
+            project_folder: temp_dir.path().to_path_buf(),
+            output: temp_dir.path().join("man"),
+            bin: None,
+        };
+        execute(&cmd, false).unwrap();
+
+        let page = fs::read_to_string(temp_dir.path().join("man/demo-cli.1")).unwrap();
+        assert!(page.contains(".SH NAME\ndemo\\-cli \\- A demo project\n"));
+        assert!(page.contains("\\fB\\-q, \\-\\-quiet\\fR\nPrint nothing\n"));
+        assert!(page.contains("Licensed under MIT License."));
+    }
+
+    #[test]
+    fn test_command_name() {
+        assert_eq!(
+            command_name("WTFM (Write The F*cking Manual)"),
+            "wtfm-write-the-f-cking-manual"
+        );
+    }
+}
@@ -1,3 +1,11 @@
 pub mod analyze;
 pub mod author;
+pub mod check;
+pub mod config;
 pub mod generate;
+pub mod man;
+pub mod scaffold;
+pub mod serve;
+pub mod site;
+pub mod templates;
+pub mod version;
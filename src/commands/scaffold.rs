@@ -0,0 +1,213 @@
+use crate::cli::args::{Commands, Layout};
+use crate::config::wtfm::{DocsTree, WtfmConfig};
+use crate::utils::cargo::{Cargo, CargoInfo};
+use crate::utils::ci::{Ecosystem, Pipeline};
+use crate::utils::diataxis::{self, Project};
+use crate::utils::git::{self, Forge, Git};
+use crate::utils::issues;
+use crate::utils::node::{Node, NodeInfo};
+use crate::utils::template::OVERRIDE_DIR;
+use colored::*;
+use std::fs;
+use std::path::Path;
+
+pub fn execute(cmd: &Commands, _debug: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if let Commands::Scaffold {
+        layout,
+        project_folder,
+        root,
+    } = cmd
+    {
+        let (config, source) =
+            WtfmConfig::find_with_defaults(project_folder).map_err(|e| e.to_string())?;
+
+        match layout {
+            Layout::Diataxis => {
+                diataxis_tree(&config, project_folder, root)?;
+                // Saved as written, without the defaults of the user
+                let mut config = source.read()?;
+                config.docs = Some(DocsTree { root: root.clone() });
+                source.save(&config)?;
+                println!("Documentation tree recorded in {}", source);
+            }
+            Layout::IssueTemplates => issue_templates(&config, project_folder)?,
+        }
+    }
+    Ok(())
+}
+
+// Create the documentation tree with starter pages generated from the project
+// metadata. Existing pages are never overwritten, so the command can be run
+// again to restore missing ones. The tree is recorded in .wtfm.json for
+// `wtfm check`.
+fn diataxis_tree(
+    config: &WtfmConfig,
+    project_folder: &Path,
+    root: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let cargo = Cargo::new(project_folder);
+    let node = Node::new(project_folder);
+    let remote = Git::new(project_folder)
+        .info()
+        .and_then(|info| info.remote_url)
+        .and_then(|url| git::web_url(&url));
+    let cargo_info = cargo.info();
+    let project = Project {
+        project_name: config.project_name.clone(),
+        description: config.description.clone(),
+        version: config.version.to_string(),
+        license: config.license.clone(),
+        package_name: cargo_info.and_then(|info| info.package_name.clone()),
+        bins: cargo_info.map(|info| info.bins.clone()).unwrap_or_default(),
+        features: cargo_info
+            .map(|info| info.features.clone())
+            .unwrap_or_default(),
+        installation: installation(cargo_info, node.info(), remote.as_deref()),
+    };
+
+    let docs_path = project_folder.join(root);
+    for (page, content) in diataxis::render(&project)? {
+        write_new(&docs_path.join(page), &content)?;
+    }
+    Ok(())
+}
+
+// Issue and pull request templates for the forge hosting the repository,
+// GitHub when it can't be told. Templates in .wtfm/templates/issues replace
+// the bundled ones, and existing files are never overwritten.
+fn issue_templates(
+    config: &WtfmConfig,
+    project_folder: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let cargo = Cargo::new(project_folder);
+    let cargo_info = cargo.info();
+    let remote = Git::new(project_folder)
+        .info()
+        .and_then(|info| info.remote_url)
+        .or_else(|| cargo_info.and_then(|info| info.repository.clone()))
+        .and_then(|url| git::web_url(&url));
+    let forge = match remote.as_deref().and_then(Forge::from_url) {
+        Some(forge) => forge,
+        None => {
+            println!("No known forge in the git remote, using GitHub templates");
+            Forge::GitHub
+        }
+    };
+
+    let ecosystems = Ecosystem::detect(project_folder);
+    let bins = cargo_info.map(|info| info.bins.clone()).unwrap_or_default();
+    let pipeline = Pipeline::detect(
+        project_folder,
+        remote.as_deref(),
+        cargo_info.and_then(|info| info.rust_version.as_deref()),
+    );
+    let project = issues::Project {
+        project_name: config.project_name.clone(),
+        version: config.version.to_string(),
+        version_commands: issues::Project::version_commands(&bins, &ecosystems),
+        checks: pipeline
+            .iter()
+            .flat_map(|pipeline| &pipeline.jobs)
+            .filter_map(|job| job.commands.last().cloned())
+            .collect(),
+    };
+
+    let overrides = project_folder.join(OVERRIDE_DIR).join(issues::TEMPLATE_SET);
+    for (file, content) in issues::render(&project, forge, Some(&overrides))? {
+        write_new(&project_folder.join(file), &content)?;
+    }
+    Ok(())
+}
+
+fn write_new(path: &Path, content: &str) -> std::io::Result<()> {
+    if path.exists() {
+        println!("{} {:?} already exists, skipped", "-".yellow(), path);
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+    println!("{} Created {:?}", "✓".green(), path);
+    Ok(())
+}
+
+// Shell commands installing the project, from the package managers it
+// publishes to. A clone of the repository is the last resort.
+fn installation(
+    cargo: Option<&CargoInfo>,
+    node: Option<&NodeInfo>,
+    remote: Option<&str>,
+) -> Vec<String> {
+    let mut steps = Vec::new();
+    if let Some(cargo) = cargo {
+        if let Some(name) = &cargo.package_name {
+            if cargo.bins.is_empty() {
+                steps.push(format!("cargo add {}", name));
+            } else {
+                steps.push(format!("cargo install {}", name));
+            }
+        }
+    }
+    if let Some(name) = node.and_then(|info| info.package_name.as_ref()) {
+        steps.push(format!("npm install {}", name));
+    }
+    if steps.is_empty() {
+        if let Some(remote) = remote {
+            steps.push(format!("git clone {}", remote));
+        }
+    }
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::diataxis::Quadrant;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_scaffold_diataxis() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n\n[features]\ndefault = []\nyaml = []\n",
+        )
+        .unwrap();
+        fs::create_dir_all(temp_dir.path().join("src")).unwrap();
+        fs::write(temp_dir.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+        let config = WtfmConfig {
+            project_name: "Demo".to_string(),
+            description: "A demo project".to_string(),
+            ..WtfmConfig::new_test()
+        };
+        config.save(temp_dir.path().join(".wtfm.json")).unwrap();
+        let explanation = temp_dir.path().join("docs/explanation");
+        fs::create_dir_all(&explanation).unwrap();
+        fs::write(explanation.join("index.md"), "# Why\n").unwrap();
+
+        let cmd = Commands::Scaffold {
+            layout: Layout::Diataxis,
+            project_folder: temp_dir.path().to_path_buf(),
+            root: "docs".into(),
+        };
+        execute(&cmd, false).unwrap();
+
+        let docs = temp_dir.path().join("docs");
+        let tutorial = fs::read_to_string(docs.join("tutorials/getting_started.md")).unwrap();
+        assert!(tutorial.contains("cargo install demo"));
+        let reference = fs::read_to_string(docs.join("reference/project.md")).unwrap();
+        assert!(reference.contains("- `yaml`"));
+        assert_eq!(
+            fs::read_to_string(explanation.join("index.md")).unwrap(),
+            "# Why\n"
+        );
+
+        let config = WtfmConfig::load(temp_dir.path().join(".wtfm.json"), false).unwrap();
+        assert_eq!(config.docs.unwrap().root, Path::new("docs"));
+        assert_eq!(
+            diataxis::empty_quadrants(&docs),
+            vec![Quadrant::HowTo, Quadrant::Explanation]
+        );
+    }
+}
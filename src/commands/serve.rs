@@ -0,0 +1,363 @@
+use crate::cli::args::Commands;
+use crate::commands::site::site_title;
+use crate::config::source;
+use crate::config::wizard::WizardAnswers;
+use crate::config::wtfm::{ConfigError, WtfmConfig};
+use crate::debug;
+use crate::utils::format::Format;
+use crate::utils::watch::Watcher;
+use crate::utils::{html, regions, site, template};
+use colored::*;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tiny_http::{Header, Request, Response, Server};
+
+const EVENTS_PATH: &str = "/__wtfm/events";
+
+// Reloads the page whenever the server announces a rebuild
+const RELOAD_SCRIPT: &str = "<script>new EventSource(\"/__wtfm/events\").onmessage = function () { location.reload(); };</script>";
+
+/// The pages of the site by URL, or the error that prevented building it.
+type Build = Result<HashMap<String, String>, String>;
+
+// Serve the README, rendered from the current configuration, and the docs/
+// pages as the site `wtfm site` builds, rebuilding it whenever the
+// configuration, the manifest, the templates or the docs change.
+// Browsers are told to reload through server-sent events, and errors are
+// shown in place of the pages so a broken template doesn't stop the server.
+pub fn execute(cmd: &Commands, debug: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if let Commands::Serve {
+        project_folder,
+        port,
+    } = cmd
+    {
+        let server = Server::http(("127.0.0.1", *port))
+            .map_err(|e| format!("Failed to listen on port {}: {}", port, e))?;
+        let build = Arc::new(Mutex::new(build(project_folder, debug)));
+        if let Err(e) = &*build.lock().unwrap() {
+            eprintln!("{} {}", "✗".red(), e);
+        }
+        let clients: Arc<Mutex<Vec<Sender<()>>>> = Arc::new(Mutex::new(Vec::new()));
+
+        let watcher = Watcher::new(
+            &source::FILES
+                .iter()
+                .chain(["README.md"].iter())
+                .map(|file| project_folder.join(file))
+                .collect::<Vec<_>>(),
+            &watched_dirs(project_folder),
+        )?;
+        {
+            let project_folder = project_folder.clone();
+            let build = Arc::clone(&build);
+            let clients = Arc::clone(&clients);
+            thread::spawn(move || {
+                while let Some(changed) = watcher.wait() {
+                    debug!("Changed: {:?}", changed);
+                    let rebuilt = self::build(&project_folder, debug);
+                    match &rebuilt {
+                        Ok(_) => {
+                            println!("{} Rebuilt after {} change(s)", "✓".green(), changed.len())
+                        }
+                        Err(e) => eprintln!("{} {}", "✗".red(), e),
+                    }
+                    *build.lock().unwrap() = rebuilt;
+                    clients
+                        .lock()
+                        .unwrap()
+                        .retain(|client| client.send(()).is_ok());
+                }
+            });
+        }
+
+        println!(
+            "Serving {:?} on http://127.0.0.1:{}/ (press Ctrl-C to stop)",
+            project_folder, port
+        );
+        for request in server.incoming_requests() {
+            if request.url() == EVENTS_PATH {
+                subscribe(request, &clients);
+                continue;
+            }
+            let response = respond(&build.lock().unwrap(), project_folder, request.url());
+            if let Err(e) = request.respond(response) {
+                debug!("Failed to respond: {}", e);
+            }
+        }
+    }
+    Ok(())
+}
+
+// Renders the README from .wtfm.json, merged into the one on disk the way
+// `generate` would, and builds the site around it
+fn build(project_folder: &Path, debug: bool) -> Build {
+    let mut sources = site::collect_sources(project_folder)
+        .map_err(|e| format!("Failed to read the pages: {}", e))?;
+
+    let config = match WtfmConfig::find_with_defaults(project_folder) {
+        Ok((config, _)) => Some(config),
+        Err(ConfigError::Missing(_)) => None,
+        Err(e) => return Err(e.to_string()),
+    };
+    if let Some(config) = config {
+        let format = Format::Markdown;
+        let document = config.readme("README.md", format);
+        let rendered = template::render(&WizardAnswers::from(&config), &document, project_folder)
+            .map_err(|e| format!("Failed to render README.md: {}", describe(&e)))?;
+
+        let readme = Path::new("README.md");
+        let existing = sources.iter().position(|source| source.path == readme);
+        let committed = existing.map_or("", |i| sources[i].markdown.as_str());
+        let markdown = regions::merge(committed, &rendered, format.markers()).content;
+        match existing {
+            Some(i) => sources[i].markdown = markdown,
+            None => sources.insert(
+                0,
+                site::Source {
+                    path: readme.to_path_buf(),
+                    markdown,
+                },
+            ),
+        }
+    }
+    if sources.is_empty() {
+        return Err("No .wtfm.json, README.md or Markdown files under docs/ to serve".to_string());
+    }
+
+    let files = site::build(&site_title(project_folder, debug), &sources)
+        .map_err(|e| format!("Failed to build the site: {}", describe(&e)))?;
+    Ok(files
+        .into_iter()
+        .map(|(path, content)| (url_path(&path), content))
+        .collect())
+}
+
+// The docs/ pages and the template directories of the configuration, such
+// as those `wtfm templates eject` creates
+fn watched_dirs(project_folder: &Path) -> Vec<PathBuf> {
+    let mut dirs = vec![
+        project_folder.join("docs"),
+        project_folder.join("templates"),
+    ];
+    if let Ok((config, _)) = WtfmConfig::find_with_defaults(project_folder) {
+        dirs.extend(
+            config
+                .template_dirs()
+                .into_iter()
+                .map(|dir| project_folder.join(dir)),
+        );
+    }
+    dirs
+}
+
+// Tera puts the actual cause of a failure in the chain of sources
+fn describe(error: &dyn Error) -> String {
+    let mut description = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        description.push_str(&format!(": {}", cause));
+        source = cause.source();
+    }
+    description
+}
+
+fn url_path(path: &Path) -> String {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn respond(build: &Build, project_folder: &Path, url: &str) -> Response<std::io::Cursor<Vec<u8>>> {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let mut path = path.trim_start_matches('/').to_string();
+    if path.is_empty() || path.ends_with('/') {
+        path.push_str("index.html");
+    }
+
+    let pages = match build {
+        Ok(pages) => pages,
+        Err(e) => return html_response(error_page(e), 500),
+    };
+    if let Some(content) = pages.get(&path) {
+        return if path.ends_with(".html") {
+            html_response(
+                content.replace("</body>", &format!("{}\n</body>", RELOAD_SCRIPT)),
+                200,
+            )
+        } else {
+            with_type(Response::from_string(content.as_str()), "application/json")
+        };
+    }
+
+    // Images and other files the pages link to come from the project
+    let escapes = Path::new(&path)
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_)));
+    let file = if escapes {
+        None
+    } else {
+        fs::read(project_folder.join(&path)).ok()
+    };
+    match file {
+        Some(content) => with_type(Response::from_data(content), content_type(&path)),
+        None => html_response(
+            html::standalone_page(
+                "Not found",
+                &format!("<p>No page at /{}</p>", html::escape(&path)),
+            ),
+            404,
+        ),
+    }
+}
+
+fn error_page(error: &str) -> String {
+    html::standalone_page(
+        "Build failed",
+        &format!(
+            "<h1>Build failed</h1>\n<pre><code>{}</code></pre>\n<p>The page reloads once the problem is fixed.</p>\n{}\n",
+            html::escape(error),
+            RELOAD_SCRIPT
+        ),
+    )
+}
+
+fn html_response(content: String, status: u16) -> Response<std::io::Cursor<Vec<u8>>> {
+    with_type(Response::from_string(content), "text/html; charset=utf-8").with_status_code(status)
+}
+
+fn with_type(
+    response: Response<std::io::Cursor<Vec<u8>>>,
+    content_type: &str,
+) -> Response<std::io::Cursor<Vec<u8>>> {
+    match Header::from_bytes("Content-Type", content_type) {
+        Ok(header) => response.with_header(header),
+        Err(_) => response,
+    }
+}
+
+fn content_type(path: &str) -> &'static str {
+    let extension = Path::new(path)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "html" => "text/html; charset=utf-8",
+        "css" => "text/css",
+        "js" => "text/javascript",
+        "json" => "application/json",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
+
+// Keeps the connection open as an event stream, sending an event on every
+// rebuild until the browser goes away
+fn subscribe(request: Request, clients: &Arc<Mutex<Vec<Sender<()>>>>) {
+    let (sender, rebuilds) = mpsc::channel();
+    clients.lock().unwrap().push(sender);
+
+    let mut writer = request.into_writer();
+    thread::spawn(move || {
+        let headers = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n";
+        if writer
+            .write_all(headers.as_bytes())
+            .and_then(|_| writer.flush())
+            .is_err()
+        {
+            return;
+        }
+        for _ in rebuilds {
+            if writer
+                .write_all(b"data: reload\n\n")
+                .and_then(|_| writer.flush())
+                .is_err()
+            {
+                break;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::args::TemplatesCommand;
+    use tempfile::TempDir;
+
+    fn body(response: Response<std::io::Cursor<Vec<u8>>>) -> (u16, String) {
+        let status = response.status_code().0;
+        let mut content = String::new();
+        std::io::Read::read_to_string(&mut response.into_reader(), &mut content).unwrap();
+        (status, content)
+    }
+
+    #[test]
+    fn test_respond() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("README.md"), "# Demo\n").unwrap();
+        fs::write(temp_dir.path().join("logo.svg"), "<svg/>").unwrap();
+        let build = build(temp_dir.path(), false);
+
+        let (status, page) = body(respond(&build, temp_dir.path(), "/?x=1"));
+        assert_eq!(status, 200);
+        assert!(page.contains("<h1 id=\"demo\">Demo</h1>"));
+        assert!(page.contains(RELOAD_SCRIPT));
+
+        assert_eq!(
+            body(respond(&build, temp_dir.path(), "/logo.svg")).1,
+            "<svg/>"
+        );
+        assert_eq!(
+            body(respond(&build, temp_dir.path(), "/../etc/passwd")).0,
+            404
+        );
+
+        let failed: Build = Err("Failed to render README.md: <oops>".to_string());
+        let (status, page) = body(respond(&failed, temp_dir.path(), "/"));
+        assert_eq!(status, 500);
+        assert!(page.contains("Failed to render README.md: &lt;oops&gt;"));
+        assert!(page.contains(RELOAD_SCRIPT));
+    }
+
+    #[test]
+    fn test_rebuild_after_ejected_template_change() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        WtfmConfig::new_test()
+            .save(root.join(".wtfm.json"))
+            .unwrap();
+        let eject = Commands::Templates {
+            command: TemplatesCommand::Eject {
+                name: "minimal".to_string(),
+                project_folder: root.clone(),
+                format: Format::Markdown,
+                output: None,
+            },
+        };
+        crate::commands::templates::execute(&eject, false).unwrap();
+
+        let dirs = watched_dirs(&root);
+        assert!(dirs.contains(&root.join(".wtfm/templates/markdown")));
+        let watcher = Watcher::new(&[], &dirs).unwrap();
+        let template = root.join(".wtfm/templates/markdown/header.tera");
+        fs::write(&template, "# {{ project_name }}\n\nEdited by hand\n").unwrap();
+        assert!(watcher.wait().unwrap().contains(&template));
+
+        let pages = build(&root, false).unwrap();
+        assert!(pages.values().any(|page| page.contains("Edited by hand")));
+    }
+}
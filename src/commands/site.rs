@@ -0,0 +1,84 @@
+use crate::cli::args::Commands;
+use crate::config::wtfm::WtfmConfig;
+use crate::debug;
+use crate::utils::cargo::Cargo;
+use crate::utils::site;
+use std::fs;
+use std::path::Path;
+
+/// Default output directory of the site, relative to the project directory.
+pub const DEFAULT_OUTPUT: &str = "target/wtfm-site";
+
+// Build a static site out of the README and the Markdown files under docs/.
+// Files already in the output directory are overwritten but never removed.
+pub fn execute(cmd: &Commands, debug: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if let Commands::Site {
+        project_folder,
+        output,
+    } = cmd
+    {
+        let sources = site::collect_sources(project_folder)?;
+        if sources.is_empty() {
+            return Err("No README.md or Markdown files under docs/ to build a site from".into());
+        }
+        debug!("Building a site from {} page(s)", sources.len());
+
+        let output = output
+            .clone()
+            .unwrap_or_else(|| project_folder.join(DEFAULT_OUTPUT));
+        for (path, content) in site::build(&site_title(project_folder, debug), &sources)? {
+            let path = output.join(path);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, content)?;
+        }
+        println!(
+            "Site with {} page(s) successfully generated at {:?}",
+            sources.len(),
+            output.join("index.html")
+        );
+    }
+    Ok(())
+}
+
+/// The project name from the configuration, or from Cargo.toml when
+/// there's none.
+pub fn site_title(project_folder: &Path, _debug: bool) -> String {
+    WtfmConfig::find(project_folder)
+        .ok()
+        .map(|(config, _)| config.project_name)
+        .or_else(|| {
+            Cargo::new(project_folder)
+                .info()
+                .and_then(|info| info.package_name.clone())
+        })
+        .unwrap_or_else(|| "Documentation".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_site_is_written_to_target() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("README.md"), "# Demo\n").unwrap();
+        fs::create_dir_all(temp_dir.path().join("docs/how-to")).unwrap();
+        fs::write(temp_dir.path().join("docs/how-to/deploy.md"), "# Deploy\n").unwrap();
+        fs::write(temp_dir.path().join("docs/notes.txt"), "not a page").unwrap();
+
+        let cmd = Commands::Site {
+            project_folder: temp_dir.path().to_path_buf(),
+            output: None,
+        };
+        execute(&cmd, false).unwrap();
+
+        let output = temp_dir.path().join(DEFAULT_OUTPUT);
+        assert!(output.join("index.html").is_file());
+        assert!(output.join("docs/how-to/deploy.html").is_file());
+        assert!(output.join(site::SEARCH_INDEX).is_file());
+        assert!(!output.join("docs/notes.html").exists());
+    }
+}
@@ -0,0 +1,133 @@
+use crate::cli::args::{Commands, TemplatesCommand};
+use crate::config::wtfm::WtfmConfig;
+use crate::utils::template::{self, VARIANTS};
+use colored::*;
+use std::fs;
+use std::path::PathBuf;
+
+pub fn execute(cmd: &Commands, _debug: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if let Commands::Templates { command } = cmd {
+        match command {
+            TemplatesCommand::List => {
+                println!("{}: {}", "Built-in Templates".bold(), "-".repeat(40));
+                for (name, description) in VARIANTS {
+                    println!("{:<10} {}", name.green(), description);
+                }
+                println!("\nSelect one with \"template\" in .wtfm.json, or copy it with `wtfm templates eject <name>`");
+            }
+            TemplatesCommand::Eject {
+                name,
+                project_folder,
+                format,
+                output,
+            } => {
+                if !VARIANTS.iter().any(|(variant, _)| variant == name) {
+                    let names: Vec<&str> = VARIANTS.iter().map(|(variant, _)| *variant).collect();
+                    return Err(format!(
+                        "Unknown template {}, expected one of {}",
+                        name,
+                        names.join(", ")
+                    )
+                    .into());
+                }
+
+                let set = format.template_set();
+                let entry = template::template_file(name);
+                let bundled = |file: &str| {
+                    template::bundled_source(set, file)
+                        .ok_or_else(|| format!("No bundled template {}/{}", set, file))
+                };
+                let mut files = vec![entry.clone()];
+                files.extend(includes(bundled(&entry)?));
+
+                let destination = output
+                    .clone()
+                    .unwrap_or_else(|| PathBuf::from(template::OVERRIDE_DIR).join(set));
+                let target = project_folder.join(&destination);
+                fs::create_dir_all(&target)?;
+                for file in &files {
+                    let path = target.join(file);
+                    if path.exists() {
+                        println!("{} {:?} already exists, skipped", "-".yellow(), path);
+                        continue;
+                    }
+                    fs::write(&path, bundled(file)?)?;
+                    println!("{} Copied {:?}", "✓".green(), path);
+                }
+
+                // Point the README at the copy so edits take effect right away
+                match WtfmConfig::find(project_folder) {
+                    Ok((mut config, source)) => {
+                        config.template_dir = Some(destination);
+                        config.template = Some(name.clone());
+                        source.save(&config)?;
+                        println!("Updated {} to use the copied templates", source);
+                    }
+                    Err(_) => println!(
+                        "Set \"template_dir\": {:?} and \"template\": {:?} in .wtfm.json to use them",
+                        destination, name
+                    ),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// Templates pulled in with `{% include "name" %}`
+fn includes(template: &str) -> Vec<String> {
+    let mut names: Vec<String> = template
+        .split("include")
+        .skip(1)
+        .filter_map(|rest| {
+            let rest = rest.trim_start().strip_prefix('"')?;
+            rest.split_once('"').map(|(name, _)| name.to_string())
+        })
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::format::Format;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_eject_copies_variant_and_partials() {
+        let temp_dir = TempDir::new().unwrap();
+        let cmd = Commands::Templates {
+            command: TemplatesCommand::Eject {
+                name: "minimal".to_string(),
+                project_folder: temp_dir.path().to_path_buf(),
+                format: Format::Markdown,
+                output: None,
+            },
+        };
+        execute(&cmd, false).unwrap();
+
+        let target = temp_dir.path().join(".wtfm/templates/markdown");
+        let mut copied: Vec<String> = fs::read_dir(&target)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        copied.sort();
+        assert_eq!(copied, vec!["header.tera", "license.tera", "minimal.tera"]);
+    }
+
+    #[test]
+    fn test_eject_rejects_unknown_template() {
+        let temp_dir = TempDir::new().unwrap();
+        let cmd = Commands::Templates {
+            command: TemplatesCommand::Eject {
+                name: "fancy".to_string(),
+                project_folder: temp_dir.path().to_path_buf(),
+                format: Format::Markdown,
+                output: None,
+            },
+        };
+        assert!(execute(&cmd, false).is_err());
+    }
+}
@@ -0,0 +1,204 @@
+use crate::cli::args::Commands;
+use crate::config::source::ConfigSource;
+use crate::config::wtfm::WtfmConfig;
+use crate::debug;
+use crate::utils::cargo::Cargo;
+use crate::utils::git::Git;
+use crate::utils::node::Node;
+use crate::utils::version;
+use colored::*;
+use semver::Version;
+use std::fs;
+
+// Report every place the project version is declared, suggest the next
+// release from the commits since the latest tag and optionally sync them all
+pub fn execute(cmd: &Commands, _debug: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if let Commands::Version {
+        project_folder,
+        apply,
+        set,
+    } = cmd
+    {
+        let config = WtfmConfig::find(project_folder).ok();
+        let cargo = Cargo::new(project_folder);
+        let node = Node::new(project_folder);
+        let git = Git::new(project_folder);
+
+        let tags = git.info().map(|info| info.tags).unwrap_or_default();
+        let latest_tag = version::latest_tag(&tags);
+
+        let sources = [
+            (
+                config
+                    .as_ref()
+                    .map_or(".wtfm.json".to_string(), |(_, source)| source.to_string()),
+                config.as_ref().map(|(c, _)| c.version.to_string()),
+            ),
+            (
+                "Cargo.toml".to_string(),
+                cargo.info().and_then(|info| info.version.clone()),
+            ),
+            (
+                "package.json".to_string(),
+                node.info().and_then(|info| info.version.clone()),
+            ),
+            (
+                "Latest git tag".to_string(),
+                latest_tag.as_ref().map(|(tag, _)| tag.clone()),
+            ),
+        ];
+
+        println!("{}: {}", "Version Sources".bold(), "-".repeat(40));
+        let mut versions: Vec<Version> = Vec::new();
+        for (source, raw) in &sources {
+            match raw {
+                None => println!("{}: {}", source, "-".dimmed()),
+                Some(raw) => match version::parse_version(raw) {
+                    Some(parsed) => {
+                        println!("{}: {}", source, raw);
+                        versions.push(parsed);
+                    }
+                    None => println!("{}: {} ({})", source, raw, "not a valid semver".yellow()),
+                },
+            }
+        }
+
+        let mut distinct = versions.clone();
+        distinct.sort();
+        distinct.dedup();
+        if distinct.len() > 1 {
+            let listed: Vec<String> = distinct.iter().map(|v| v.to_string()).collect();
+            println!(
+                "{}",
+                format!("Version sources disagree: {}", listed.join(", ")).red()
+            );
+        } else if !distinct.is_empty() {
+            println!("{}", "All version sources agree".green());
+        }
+
+        let target = match set {
+            Some(raw) => version::parse_version(raw)
+                .ok_or_else(|| format!("'{}' is not a valid semantic version", raw))?,
+            None => {
+                let suggested = suggest(&git, latest_tag.as_ref(), &distinct);
+                match suggested {
+                    Some(target) => target,
+                    None => {
+                        println!("No version found to suggest from");
+                        return Ok(());
+                    }
+                }
+            }
+        };
+        println!("{}: {}", "Next version".bold(), target.to_string().cyan());
+
+        if *apply {
+            apply_version(&cargo, &node, config, &target)?;
+        }
+    }
+    Ok(())
+}
+
+// The next release is the latest tag bumped according to the commits made
+// since then. Untagged projects simply sync to the highest declared version.
+fn suggest(
+    git: &Git,
+    latest_tag: Option<&(String, Version)>,
+    declared: &[Version],
+) -> Option<Version> {
+    match latest_tag {
+        Some((tag, tagged)) => {
+            let commits = git.commits_since(Some(tag));
+            debug!("{} commit(s) since {}", commits.len(), tag);
+            match version::suggest_bump(&commits) {
+                Some(bump) => {
+                    println!(
+                        "{} commit(s) since {}: {} release",
+                        commits.len(),
+                        tag,
+                        bump
+                    );
+                    Some(version::apply_bump(tagged, bump))
+                }
+                None => {
+                    println!("No commits since {}", tag);
+                    let highest = declared.last().filter(|v| *v > tagged);
+                    Some(highest.unwrap_or(tagged).clone())
+                }
+            }
+        }
+        None => declared.last().cloned(),
+    }
+}
+
+fn apply_version(
+    cargo: &Cargo,
+    node: &Node,
+    config: Option<(WtfmConfig, ConfigSource)>,
+    target: &Version,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if cargo.info().is_some() {
+        let cargo_path = cargo.path().join("Cargo.toml");
+        let content = fs::read_to_string(&cargo_path)?;
+        match version::set_cargo_version(&content, target)? {
+            Some(updated) => {
+                fs::write(&cargo_path, updated)?;
+                println!("Updated {:?} to {}", cargo_path, target);
+            }
+            None => println!("Skipped {:?}: no literal version field", cargo_path),
+        }
+    }
+
+    if node.info().is_some() {
+        let package_json_path = node.package_json_path();
+        let content = fs::read_to_string(&package_json_path)?;
+        match version::set_package_json_version(&content, target) {
+            Some(updated) => {
+                fs::write(&package_json_path, updated)?;
+                println!("Updated {:?} to {}", package_json_path, target);
+            }
+            None => println!("Skipped {:?}: no version field", package_json_path),
+        }
+    }
+
+    if let Some((mut config, source)) = config {
+        config.version = target.clone();
+        source.save(&config)?;
+        println!("Updated {} to {}", source, target);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_apply_syncs_manifests() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("package.json"),
+            "{\n  \"name\": \"demo\",\n  \"version\": \"0.0.9\"\n}\n",
+        )
+        .unwrap();
+
+        let cmd = Commands::Version {
+            project_folder: temp_dir.path().to_path_buf(),
+            apply: true,
+            set: Some("0.2.0".to_string()),
+        };
+        execute(&cmd, false).unwrap();
+
+        let cargo = Cargo::new(temp_dir.path());
+        let node = Node::new(temp_dir.path());
+        assert_eq!(cargo.info().unwrap().version.as_deref(), Some("0.2.0"));
+        assert_eq!(node.info().unwrap().version.as_deref(), Some("0.2.0"));
+    }
+}
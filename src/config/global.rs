@@ -0,0 +1,226 @@
+use crate::config::wizard::{author_from_interactive, select_badges, WizardAnswers};
+use crate::config::wtfm::{Author, ConfigError};
+use crate::licenses::License;
+use crate::utils::badges::Badge;
+use dialoguer::{Input, Select};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Defaults of the user for every project, in `wtfm/config.json` under the
+/// XDG config directory. Project configurations override them, and they
+/// override what the wizard proposes.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct GlobalConfig {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<Author>,
+    /// SPDX expression, e.g. `MIT OR Apache-2.0`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub setup_ci: Option<bool>,
+    /// README variant, a built-in one like `minimal`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub badges: Vec<Badge>,
+}
+
+/// Where the user defaults live: `$XDG_CONFIG_HOME/wtfm/config.json`,
+/// `~/.config/wtfm/config.json` without it, or under `%APPDATA%` on Windows.
+pub fn path() -> Option<PathBuf> {
+    let non_empty = |name| env::var_os(name).filter(|value| !value.is_empty());
+    let config_dir = non_empty("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| non_empty("HOME").map(|home| Path::new(&home).join(".config")))
+        .or_else(|| non_empty("APPDATA").map(PathBuf::from))?;
+    Some(config_dir.join("wtfm").join("config.json"))
+}
+
+impl GlobalConfig {
+    /// The user defaults, empty when there are none.
+    pub fn load() -> Result<Self, ConfigError> {
+        match path() {
+            Some(path) => Self::read(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// The defaults at `path`, empty when the file doesn't exist.
+    pub fn read(path: &Path) -> Result<Self, ConfigError> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(ConfigError::Unreadable(path.to_path_buf(), e)),
+        };
+        let defaults: Self =
+            serde_json::from_str(&content).map_err(|e| ConfigError::invalid(path, e))?;
+        defaults
+            .check()
+            .map_err(|message| ConfigError::located(path, message, None))?;
+        Ok(defaults)
+    }
+
+    /// Checks what deserializing doesn't, the license expression.
+    pub fn check(&self) -> Result<(), String> {
+        match &self.license {
+            Some(license) => License::from_expression(license)
+                .map(|_| ())
+                .map_err(|message| format!("license: {}", message)),
+            None => Ok(()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Settings a project configuration takes from the defaults when it
+    /// doesn't have them, in its layout.
+    pub fn project_settings(&self) -> Map<String, Value> {
+        let mut settings = match serde_json::to_value(self) {
+            Ok(Value::Object(settings)) => settings,
+            _ => Map::new(),
+        };
+        if !self.authors.is_empty() {
+            settings.insert(
+                "author_quantity".to_string(),
+                Value::from(self.authors.len()),
+            );
+        }
+        settings
+    }
+
+    /// `answers` with the defaults of the user in place of those of wtfm.
+    pub fn wizard_defaults(&self, mut answers: WizardAnswers) -> WizardAnswers {
+        if !self.authors.is_empty() {
+            answers.author_quantity = self.authors.len() as u32;
+            answers.authors = self.authors.clone();
+        }
+        if let Some(license) = self
+            .license
+            .as_deref()
+            .and_then(|license| License::from_expression(license).ok())
+        {
+            answers.license = license;
+        }
+        if let Some(setup_ci) = self.setup_ci {
+            answers.setup_ci = setup_ci;
+        }
+        if !self.badges.is_empty() {
+            answers.content.badges = self.badges.clone();
+        }
+        answers
+    }
+
+    /// Asks for every default of the user, proposing the current ones.
+    /// Empty answers leave the choice to the wizard of each project.
+    pub fn from_interactive(&self) -> Self {
+        let author_quantity: usize = Input::new()
+            .with_prompt("How many default authors?")
+            .default(self.authors.len())
+            .interact()
+            .expect("Failed to get authors quantity");
+        let authors = (0..author_quantity)
+            .map(|i| author_from_interactive(self.authors.get(i)))
+            .collect();
+
+        let license: String = Input::new()
+            .with_prompt("License, as an SPDX expression (optional)")
+            .default(self.license.clone().unwrap_or_default())
+            .allow_empty(true)
+            .validate_with(|input: &String| -> Result<(), String> {
+                match input.trim() {
+                    "" => Ok(()),
+                    expression => License::from_expression(expression).map(|_| ()),
+                }
+            })
+            .interact()
+            .expect("Failed to get license");
+
+        let setup_ci = match Select::new()
+            .with_prompt("Setup CI?")
+            .items(&["Yes", "No", "Ask for each project"])
+            .default(match self.setup_ci {
+                Some(true) => 0,
+                Some(false) => 1,
+                None => 2,
+            })
+            .interact()
+            .expect("Failed to get CI choice")
+        {
+            0 => Some(true),
+            1 => Some(false),
+            _ => None,
+        };
+
+        let template: String = Input::new()
+            .with_prompt("README template (optional)")
+            .default(self.template.clone().unwrap_or_default())
+            .allow_empty(true)
+            .interact()
+            .expect("Failed to get template");
+
+        Self {
+            authors,
+            license: Some(license.trim().to_string()).filter(|license| !license.is_empty()),
+            setup_ci,
+            template: Some(template.trim().to_string()).filter(|template| !template.is_empty()),
+            badges: select_badges(&self.badges),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn defaults() -> GlobalConfig {
+        GlobalConfig {
+            authors: vec![Author {
+                name: "Jane Doe".to_string(),
+                email: "jane@example.com".to_string(),
+                url: None,
+                role: None,
+            }],
+            license: Some("MIT OR Apache-2.0".to_string()),
+            setup_ci: Some(false),
+            template: None,
+            badges: vec![Badge::Ci],
+        }
+    }
+
+    #[test]
+    fn test_save_and_read() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("wtfm").join("config.json");
+        assert_eq!(GlobalConfig::read(&path).unwrap(), GlobalConfig::default());
+
+        defaults().save(&path).unwrap();
+        assert_eq!(GlobalConfig::read(&path).unwrap(), defaults());
+
+        fs::write(&path, r#"{ "license": "MIT OR" }"#).unwrap();
+        assert!(GlobalConfig::read(&path).is_err());
+    }
+
+    #[test]
+    fn test_defaults_of_the_wizard() {
+        let answers = defaults().wizard_defaults(WizardAnswers::default());
+        assert_eq!(answers.authors, defaults().authors);
+        assert_eq!(answers.license.spdx_id, "MIT OR Apache-2.0");
+        assert!(!answers.setup_ci);
+        assert_eq!(answers.content.badges, vec![Badge::Ci]);
+
+        let settings = defaults().project_settings();
+        assert_eq!(settings["author_quantity"], 1);
+        assert!(!settings.contains_key("template"));
+    }
+}
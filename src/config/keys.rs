@@ -0,0 +1,240 @@
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// A step of a dotted path like `authors[0].email`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// A setting of the configuration, addressed with a dotted path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyPath(Vec<Segment>);
+
+impl KeyPath {
+    pub fn parse(path: &str) -> Result<Self, String> {
+        let invalid = || format!("Invalid key `{}`, expected e.g. `authors[0].email`", path);
+        let mut segments = Vec::new();
+        for part in path.split('.') {
+            let (key, mut indexes) = match part.find('[') {
+                Some(bracket) => part.split_at(bracket),
+                None => (part, ""),
+            };
+            if key.is_empty() && segments.is_empty() {
+                return Err(invalid());
+            }
+            if !key.is_empty() {
+                segments.push(Segment::Key(key.to_string()));
+            }
+            while !indexes.is_empty() {
+                let (index, rest) = indexes
+                    .strip_prefix('[')
+                    .and_then(|indexes| indexes.split_once(']'))
+                    .ok_or_else(invalid)?;
+                segments.push(Segment::Index(index.parse().map_err(|_| invalid())?));
+                indexes = rest;
+            }
+            if key.is_empty() && part.is_empty() {
+                return Err(invalid());
+            }
+        }
+        Ok(Self(segments))
+    }
+
+    /// The value at this path, if there's one.
+    pub fn get<'a>(&self, value: &'a Value) -> Option<&'a Value> {
+        self.0
+            .iter()
+            .try_fold(value, |value, segment| match segment {
+                Segment::Key(key) => value.get(key),
+                Segment::Index(index) => value.get(index),
+            })
+    }
+
+    /// Sets the value at this path. Missing keys are created, an index one
+    /// past the end of a list appends to it.
+    pub fn set(&self, root: &mut Value, new: Value) -> Result<(), String> {
+        let mut value = root;
+        for (i, segment) in self.0.iter().enumerate() {
+            let last = i + 1 == self.0.len();
+            value = match segment {
+                Segment::Key(key) => {
+                    if value.is_null() {
+                        *value = Value::Object(Map::new());
+                    }
+                    let object = value
+                        .as_object_mut()
+                        .ok_or_else(|| format!("`{}` is not a table", self.prefix(i)))?;
+                    object.entry(key.clone()).or_insert(Value::Null)
+                }
+                Segment::Index(index) => {
+                    if value.is_null() {
+                        *value = Value::Array(Vec::new());
+                    }
+                    let list = value
+                        .as_array_mut()
+                        .ok_or_else(|| format!("`{}` is not a list", self.prefix(i)))?;
+                    if *index == list.len() {
+                        list.push(Value::Null);
+                    }
+                    let len = list.len();
+                    list.get_mut(*index).ok_or_else(|| {
+                        format!(
+                            "`{}` has {} item(s), {} is out of range",
+                            self.prefix(i),
+                            len,
+                            index
+                        )
+                    })?
+                }
+            };
+            if last {
+                *value = new;
+                return Ok(());
+            }
+        }
+        Err("Empty key".to_string())
+    }
+
+    /// Removes the value at this path and returns it.
+    pub fn unset(&self, root: &mut Value) -> Option<Value> {
+        let (last, parents) = self.0.split_last()?;
+        let parent = parents
+            .iter()
+            .try_fold(root, |value, segment| match segment {
+                Segment::Key(key) => value.get_mut(key),
+                Segment::Index(index) => value.get_mut(index),
+            })?;
+        match last {
+            Segment::Key(key) => parent.as_object_mut()?.remove(key),
+            Segment::Index(index) => {
+                let list = parent.as_array_mut()?;
+                (*index < list.len()).then(|| list.remove(*index))
+            }
+        }
+    }
+
+    // The path up to, not including, segment `end`
+    fn prefix(&self, end: usize) -> KeyPath {
+        KeyPath(self.0[..end].to_vec())
+    }
+}
+
+impl fmt::Display for KeyPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.0.iter().enumerate() {
+            match segment {
+                Segment::Key(key) if i == 0 => write!(f, "{}", key)?,
+                Segment::Key(key) => write!(f, ".{}", key)?,
+                Segment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Every setting of `value` with its dotted path, empty lists and tables
+/// included so they can be told apart from missing ones.
+pub fn flatten(value: &Value) -> Vec<(String, &Value)> {
+    fn walk<'a>(path: KeyPath, value: &'a Value, settings: &mut Vec<(String, &'a Value)>) {
+        let child = |segment| {
+            let mut path = path.clone();
+            path.0.push(segment);
+            path
+        };
+        match value {
+            Value::Object(object) if !object.is_empty() => {
+                for (key, value) in object {
+                    walk(child(Segment::Key(key.clone())), value, settings);
+                }
+            }
+            Value::Array(list) if !list.is_empty() => {
+                for (index, value) in list.iter().enumerate() {
+                    walk(child(Segment::Index(index)), value, settings);
+                }
+            }
+            _ => settings.push((path.to_string(), value)),
+        }
+    }
+
+    let mut settings = Vec::new();
+    walk(KeyPath(Vec::new()), value, &mut settings);
+    settings
+}
+
+/// A value typed on the command line: JSON when it parses as such, a plain
+/// string otherwise, so `1.2.0` or `Jane Doe` need no quotes.
+pub fn parse_value(raw: &str) -> Value {
+    serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_and_display() {
+        let path = KeyPath::parse("authors[0].email").unwrap();
+        assert_eq!(
+            path.0,
+            vec![
+                Segment::Key("authors".to_string()),
+                Segment::Index(0),
+                Segment::Key("email".to_string())
+            ]
+        );
+        assert_eq!(path.to_string(), "authors[0].email");
+        for invalid in ["", "authors[", "authors[x]", "authors..email", "[0]"] {
+            assert!(KeyPath::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_get_set_unset() {
+        let mut config = json!({ "authors": [{ "name": "Jane" }], "setup_ci": true });
+        let email = KeyPath::parse("authors[0].email").unwrap();
+        assert!(email.get(&config).is_none());
+        email.set(&mut config, json!("jane@example.com")).unwrap();
+        assert_eq!(email.get(&config), Some(&json!("jane@example.com")));
+
+        let next = KeyPath::parse("authors[1].name").unwrap();
+        next.set(&mut config, json!("Joe")).unwrap();
+        let far = KeyPath::parse("authors[5].name").unwrap();
+        assert!(far.set(&mut config, json!("Nobody")).is_err());
+        let nested = KeyPath::parse("setup_ci.enabled").unwrap();
+        assert_eq!(
+            nested.set(&mut config, json!(true)).unwrap_err(),
+            "`setup_ci` is not a table"
+        );
+
+        let first = KeyPath::parse("authors[0]").unwrap();
+        assert_eq!(first.unset(&mut config).unwrap()["name"], "Jane");
+        assert_eq!(config["authors"], json!([{ "name": "Joe" }]));
+        assert!(KeyPath::parse("missing")
+            .unwrap()
+            .unset(&mut config)
+            .is_none());
+    }
+
+    #[test]
+    fn test_flatten_and_parse_value() {
+        let config = json!({ "authors": [{ "name": "Jane" }], "features": [], "version": "0.1.0" });
+        let settings: Vec<String> = flatten(&config)
+            .iter()
+            .map(|(key, value)| format!("{} = {}", key, value))
+            .collect();
+        assert_eq!(
+            settings,
+            vec![
+                "authors[0].name = \"Jane\"",
+                "features = []",
+                "version = \"0.1.0\""
+            ]
+        );
+        assert_eq!(parse_value("1.2.0"), json!("1.2.0"));
+        assert_eq!(parse_value("true"), json!(true));
+        assert_eq!(parse_value("[\"ci\"]"), json!(["ci"]));
+    }
+}
@@ -0,0 +1,121 @@
+use crate::config::wtfm::Author;
+use serde_json::{Map, Value};
+
+/// Layout of .wtfm.json this version of wtfm reads and writes.
+pub const SCHEMA_VERSION: u64 = 2;
+
+/// A step from one layout to the next, applied to the raw JSON.
+struct Migration {
+    /// Version the step upgrades from
+    from: u64,
+    description: &'static str,
+    apply: fn(&mut Map<String, Value>),
+}
+
+const MIGRATIONS: [Migration; 2] = [
+    Migration {
+        from: 1,
+        description: "license object replaced by its SPDX expression",
+        apply: license_to_expression,
+    },
+    Migration {
+        from: 1,
+        description: "authors given as \"Name <email>\" split into name and email",
+        apply: structured_authors,
+    },
+];
+
+/// Schema version of a configuration, 1 for files written before
+/// `schema_version` existed.
+pub fn schema_version(config: &Value) -> Option<u64> {
+    match config.get("schema_version") {
+        None => Some(1),
+        Some(version) => version.as_u64(),
+    }
+}
+
+/// Upgrades `config` to `SCHEMA_VERSION` in place. Returns the steps that
+/// changed something, none when it was already current.
+pub fn migrate(config: &mut Value) -> Result<Vec<&'static str>, String> {
+    let version =
+        schema_version(config).ok_or("`schema_version` must be a positive integer".to_string())?;
+    if version > SCHEMA_VERSION {
+        return Err(format!(
+            "schema version {} is newer than {} this wtfm supports, upgrade wtfm",
+            version, SCHEMA_VERSION
+        ));
+    }
+    let Value::Object(map) = config else {
+        return Err("the configuration must be a JSON object".to_string());
+    };
+
+    let mut applied = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|m| m.from >= version) {
+        let before = map.clone();
+        (migration.apply)(map);
+        if *map != before {
+            applied.push(migration.description);
+        }
+    }
+    if version < SCHEMA_VERSION {
+        map.insert("schema_version".to_string(), Value::from(SCHEMA_VERSION));
+        if applied.is_empty() {
+            applied.push("schema_version recorded");
+        }
+    }
+    Ok(applied)
+}
+
+fn license_to_expression(config: &mut Map<String, Value>) {
+    let spdx_id = config
+        .get("license")
+        .and_then(|license| license.get("spdx_id"))
+        .and_then(Value::as_str)
+        .map(String::from);
+    if let Some(spdx_id) = spdx_id {
+        config.insert("license".to_string(), Value::String(spdx_id));
+    }
+}
+
+fn structured_authors(config: &mut Map<String, Value>) {
+    let Some(Value::Array(authors)) = config.get_mut("authors") else {
+        return;
+    };
+    for author in authors.iter_mut() {
+        if let Value::String(text) = author {
+            *author = serde_json::to_value(Author::parse(text)).unwrap_or_default();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_migrate_from_first_layout() {
+        let mut config = json!({
+            "project_name": "Demo",
+            "license": { "spdx_id": "MIT", "name": "MIT License" },
+            "authors": ["Jane Doe <jane@example.com>", { "name": "Joe", "email": "" }]
+        });
+        let applied = migrate(&mut config).unwrap();
+        assert_eq!(applied.len(), 2);
+        assert_eq!(config["schema_version"], json!(SCHEMA_VERSION));
+        assert_eq!(config["license"], json!("MIT"));
+        assert_eq!(
+            config["authors"][0],
+            json!({ "name": "Jane Doe", "email": "jane@example.com" })
+        );
+
+        assert!(migrate(&mut config).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_migrate_rejects_newer_schema() {
+        let mut config = json!({ "schema_version": SCHEMA_VERSION + 1 });
+        assert!(migrate(&mut config).unwrap_err().contains("upgrade wtfm"));
+        assert!(migrate(&mut json!({ "schema_version": "two" })).is_err());
+    }
+}
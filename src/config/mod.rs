@@ -1,3 +1,8 @@
+pub mod global;
+pub mod keys;
+pub mod migrate;
+pub mod schema;
+pub mod source;
 pub mod wizard;
 pub mod wtfm;
 
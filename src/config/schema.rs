@@ -0,0 +1,292 @@
+use crate::config::migrate;
+use crate::config::source::{line_column, ConfigSource};
+use crate::config::wtfm::{ConfigError, WtfmConfig};
+use schemars::schema::{RootSchema, Schema, SchemaObject, SingleOrVec};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+
+/// JSON Schema of .wtfm.json, generated from `WtfmConfig`.
+pub fn schema() -> RootSchema {
+    let mut schema = schemars::schema_for!(WtfmConfig);
+    if let Some(metadata) = schema.schema.metadata.as_mut() {
+        metadata.title = Some(".wtfm.json".to_string());
+        metadata.description = Some("Configuration of wtfm".to_string());
+    }
+    schema
+}
+
+/// Something `validate` found that doesn't prevent reading the file.
+#[derive(Debug, PartialEq)]
+pub struct Warning {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+/// Checks the configuration in `source`. Errors are those reading it
+/// reports, warnings point at keys the schema doesn't know, usually typos
+/// wtfm would silently ignore, and at an outdated schema version.
+pub fn validate(source: &ConfigSource) -> Result<Vec<Warning>, ConfigError> {
+    source.read()?;
+    let content = fs::read_to_string(&source.path)
+        .map_err(|e| ConfigError::Unreadable(source.path.clone(), e))?;
+    let value = source.parse(&content)?;
+
+    // Keys of an older layout are checked once migrated, so only typos
+    // are reported
+    let mut migrated = value.clone();
+    migrate::migrate(&mut migrated)
+        .map_err(|message| ConfigError::located(&source.path, message, None))?;
+
+    let schema = schema();
+    let mut unknown = Vec::new();
+    unknown_keys(
+        &Schema::Object(schema.schema.clone()),
+        &schema.definitions,
+        &migrated,
+        "",
+        &mut unknown,
+    );
+    let mut warnings: Vec<Warning> = unknown
+        .into_iter()
+        .map(|(path, key)| {
+            let (line, column) = locate_key(&content, &key);
+            Warning {
+                line,
+                column,
+                message: format!("unknown key `{}`", path),
+            }
+        })
+        .collect();
+
+    if migrate::schema_version(&value) < Some(migrate::SCHEMA_VERSION) {
+        let (line, column) = locate_key(&content, "schema_version");
+        warnings.push(Warning {
+            line,
+            column,
+            message: "outdated layout, run `wtfm config migrate`".to_string(),
+        });
+    }
+    warnings.sort_by_key(|warning| (warning.line, warning.column));
+    Ok(warnings)
+}
+
+// Pushes the path and last key of every key of `value` that `schema`
+// doesn't describe, descending into the objects and arrays it does
+fn unknown_keys(
+    schema: &Schema,
+    definitions: &BTreeMap<String, Schema>,
+    value: &Value,
+    path: &str,
+    unknown: &mut Vec<(String, String)>,
+) {
+    let mut branches = Vec::new();
+    branches_of(schema, definitions, &mut branches);
+    match value {
+        Value::Object(object) => {
+            let open = branches.iter().any(|branch| {
+                branch
+                    .object
+                    .as_ref()
+                    .is_some_and(|object| object.additional_properties.is_some())
+            });
+            for (key, value) in object {
+                let child = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                let property = branches.iter().find_map(|branch| {
+                    branch
+                        .object
+                        .as_ref()
+                        .and_then(|object| object.properties.get(key))
+                });
+                match property {
+                    Some(property) => unknown_keys(property, definitions, value, &child, unknown),
+                    None if !open => unknown.push((child, key.clone())),
+                    None => {}
+                }
+            }
+        }
+        Value::Array(items) => {
+            let item =
+                branches
+                    .iter()
+                    .find_map(|branch| match branch.array.as_ref()?.items.as_ref()? {
+                        SingleOrVec::Single(item) => Some(item.as_ref()),
+                        SingleOrVec::Vec(_) => None,
+                    });
+            if let Some(item) = item {
+                for (index, value) in items.iter().enumerate() {
+                    let child = format!("{}[{}]", path, index);
+                    unknown_keys(item, definitions, value, &child, unknown);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+// The object schemas `schema` stands for, following references and the
+// alternatives of `anyOf`, `allOf` and `oneOf`
+fn branches_of<'a>(
+    schema: &'a Schema,
+    definitions: &'a BTreeMap<String, Schema>,
+    branches: &mut Vec<&'a SchemaObject>,
+) {
+    let Schema::Object(object) = schema else {
+        return;
+    };
+    if let Some(definition) = object
+        .reference
+        .as_deref()
+        .and_then(|reference| reference.strip_prefix("#/definitions/"))
+        .and_then(|name| definitions.get(name))
+    {
+        branches_of(definition, definitions, branches);
+    }
+    branches.push(object);
+    if let Some(subschemas) = object.subschemas.as_ref() {
+        let alternatives = [&subschemas.all_of, &subschemas.any_of, &subschemas.one_of];
+        for schema in alternatives.into_iter().flatten().flatten() {
+            branches_of(schema, definitions, branches);
+        }
+    }
+}
+
+// Line and column where `key` is set in `content`, as `"key":` in JSON,
+// `key =` in TOML or `key:` in YAML, or the start of the file when it's not
+// there
+fn locate_key(content: &str, key: &str) -> (usize, usize) {
+    let offset = content.match_indices(key).find_map(|(offset, _)| {
+        let before = content[..offset]
+            .strip_suffix('"')
+            .unwrap_or(&content[..offset]);
+        let after = &content[offset + key.len()..];
+        let after = after.strip_prefix('"').unwrap_or(after).trim_start();
+        let starts_key = before
+            .chars()
+            .last()
+            .is_none_or(|c| c.is_whitespace() || c == '{' || c == ',');
+        (starts_key && (after.starts_with(':') || after.starts_with('='))).then_some(before.len())
+    });
+    offset.map_or((1, 1), |offset| line_column(content, offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_describes_config() {
+        let schema = serde_json::to_value(schema()).unwrap();
+        assert_eq!(schema["title"], ".wtfm.json");
+        let properties = &schema["properties"];
+        assert_eq!(properties["license"]["type"], "string");
+        // flattened README content sits at the top level
+        assert!(properties.get("badges").is_some());
+        let required: Vec<&str> = schema["required"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(Value::as_str)
+            .collect();
+        assert!(required.contains(&"project_name"));
+        assert!(!required.contains(&"template"));
+        assert!(schema["definitions"]["Author"].is_object());
+        assert!(properties.get("$schema").is_some());
+    }
+
+    #[test]
+    fn test_save_keeps_schema_key() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join(".wtfm.json");
+        let config = WtfmConfig {
+            schema: Some("./wtfm.schema.json".to_string()),
+            ..WtfmConfig::new_test()
+        };
+        let source = ConfigSource::file(&path);
+        source.save(&config).unwrap();
+        assert_eq!(
+            source.read().unwrap().schema.as_deref(),
+            Some("./wtfm.schema.json")
+        );
+        assert!(validate(&source).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_validate_warns_about_unknown_keys() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join(".wtfm.json");
+        let content = r#"{
+  "$schema": "./wtfm.schema.json",
+  "schema_version": 2,
+  "project_name": "Demo",
+  "description": "A demo",
+  "version": "0.1.0",
+  "license": "MIT",
+  "setup_ci": false,
+  "author_quantity": 0,
+  "authors": [{ "name": "Jane", "email": "jane@example.com", "rloe": "Lead" }],
+  "git_info": null,
+  "cargo_info": null,
+  "feautres": ["typo"]
+}"#;
+        fs::write(&path, content).unwrap();
+        let source = ConfigSource::file(&path);
+        assert_eq!(
+            validate(&source).unwrap(),
+            vec![
+                Warning {
+                    line: 10,
+                    column: 62,
+                    message: "unknown key `authors[0].rloe`".to_string()
+                },
+                Warning {
+                    line: 13,
+                    column: 3,
+                    message: "unknown key `feautres`".to_string()
+                }
+            ]
+        );
+
+        // A valid older layout only warns that it's outdated
+        let v1 = content
+            .replace("\"schema_version\": 2", "\"schema_version\": 1")
+            .replace(
+                "\"license\": \"MIT\"",
+                "\"license\": { \"name\": \"MIT License\", \"spdx_id\": \"MIT\" }",
+            );
+        fs::write(&path, &v1).unwrap();
+        let messages: Vec<String> = validate(&source)
+            .unwrap()
+            .into_iter()
+            .map(|warning| warning.message)
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "outdated layout, run `wtfm config migrate`",
+                "unknown key `authors[0].rloe`",
+                "unknown key `feautres`",
+            ]
+        );
+
+        fs::write(&path, content.replace("\"MIT\"", "\"MIT OR\"")).unwrap();
+        let error = validate(&source).unwrap_err();
+        assert!(error.to_string().contains("at line 7"), "{}", error);
+    }
+}
@@ -0,0 +1,398 @@
+use crate::config::global::GlobalConfig;
+use crate::config::migrate;
+use crate::config::wtfm::{ConfigError, WtfmConfig};
+use crate::debug;
+use crate::utils::version;
+use serde_json::{Map, Value};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How a configuration is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceKind {
+    Json,
+    Toml,
+    Yaml,
+    /// `[package.metadata.wtfm]` of Cargo.toml
+    CargoPackage,
+    /// `[workspace.metadata.wtfm]` of Cargo.toml
+    CargoWorkspace,
+    /// The `"wtfm"` key of package.json
+    PackageJson,
+}
+
+/// Where a configuration is read from and saved to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigSource {
+    pub kind: SourceKind,
+    pub path: PathBuf,
+}
+
+/// Files a configuration may live in, so watchers can follow them all.
+pub const FILES: [&str; 5] = [
+    ".wtfm.json",
+    ".wtfm.toml",
+    ".wtfm.yaml",
+    "Cargo.toml",
+    "package.json",
+];
+
+impl ConfigSource {
+    /// Every source of the project in `project_folder`, by precedence:
+    ///
+    /// 1. `.wtfm.json`
+    /// 2. `.wtfm.toml`
+    /// 3. `.wtfm.yaml`
+    /// 4. `[package.metadata.wtfm]` in Cargo.toml
+    /// 5. `[workspace.metadata.wtfm]` in Cargo.toml
+    /// 6. `"wtfm"` in package.json
+    pub fn candidates(project_folder: &Path) -> Vec<ConfigSource> {
+        let source = |kind, file: &str| ConfigSource {
+            kind,
+            path: project_folder.join(file),
+        };
+        vec![
+            source(SourceKind::Json, ".wtfm.json"),
+            source(SourceKind::Toml, ".wtfm.toml"),
+            source(SourceKind::Yaml, ".wtfm.yaml"),
+            source(SourceKind::CargoPackage, "Cargo.toml"),
+            source(SourceKind::CargoWorkspace, "Cargo.toml"),
+            source(SourceKind::PackageJson, "package.json"),
+        ]
+    }
+
+    /// The first of `candidates` holding a configuration, or `.wtfm.json`
+    /// when none does, where new configurations go.
+    pub fn find(project_folder: &Path) -> ConfigSource {
+        let mut candidates = Self::candidates(project_folder);
+        match candidates.iter().position(ConfigSource::exists) {
+            Some(index) => candidates.swap_remove(index),
+            None => candidates.swap_remove(0),
+        }
+    }
+
+    /// A dedicated configuration file, in the format its extension gives.
+    pub fn file(path: &Path) -> ConfigSource {
+        let kind = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => SourceKind::Toml,
+            Some("yaml") | Some("yml") => SourceKind::Yaml,
+            _ => SourceKind::Json,
+        };
+        ConfigSource {
+            kind,
+            path: path.to_path_buf(),
+        }
+    }
+
+    /// Whether there's a configuration here. Manifests only count when
+    /// they have a wtfm section.
+    pub fn exists(&self) -> bool {
+        if !self.path.is_file() {
+            return false;
+        }
+        match self.kind {
+            SourceKind::Json | SourceKind::Toml | SourceKind::Yaml => true,
+            SourceKind::CargoPackage | SourceKind::CargoWorkspace => fs::read_to_string(&self.path)
+                .ok()
+                .and_then(|content| content.parse::<toml::Table>().ok())
+                .is_some_and(|manifest| self.cargo_section(&manifest).is_some()),
+            SourceKind::PackageJson => fs::read_to_string(&self.path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+                .is_some_and(|package| package.get("wtfm").is_some()),
+        }
+    }
+
+    pub fn read(&self) -> Result<WtfmConfig, ConfigError> {
+        self.read_migrated().map(|(config, _)| config)
+    }
+
+    /// Reads the configuration, taking the settings it lacks from the
+    /// defaults of the user.
+    pub fn read_with(&self, defaults: &GlobalConfig) -> Result<WtfmConfig, ConfigError> {
+        self.read_merged(&defaults.project_settings())
+            .map(|(config, _)| config)
+    }
+
+    /// Reads the configuration, upgrading older layouts in memory, and
+    /// returns the migrations applied.
+    pub fn read_migrated(&self) -> Result<(WtfmConfig, Vec<&'static str>), ConfigError> {
+        self.read_merged(&Map::new())
+    }
+
+    fn read_merged(
+        &self,
+        defaults: &Map<String, Value>,
+    ) -> Result<(WtfmConfig, Vec<&'static str>), ConfigError> {
+        let path = self.path.as_path();
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(ConfigError::Missing(path.to_path_buf()))
+            }
+            Err(e) => return Err(ConfigError::Unreadable(path.to_path_buf(), e)),
+        };
+        debug!("File content: {}", content);
+
+        let mut value = self.parse(&content)?;
+        let applied = migrate::migrate(&mut value)
+            .map_err(|message| ConfigError::located(path, message, None))?;
+        for migration in &applied {
+            debug!("Migrated {}: {}", self, migration);
+        }
+        let mut merged = false;
+        if let Value::Object(settings) = &mut value {
+            for (key, default) in defaults {
+                if !settings.contains_key(key) {
+                    debug!("{} taken from the user defaults", key);
+                    settings.insert(key.clone(), default.clone());
+                    merged = true;
+                }
+            }
+        }
+
+        // Parsing the file as written locates errors in it
+        let config = match self.kind {
+            _ if !applied.is_empty() || merged => {
+                serde_json::from_value(value).map_err(|e| ConfigError::invalid(path, e))
+            }
+            SourceKind::Json => {
+                serde_json::from_str(&content).map_err(|e| ConfigError::invalid(path, e))
+            }
+            SourceKind::Toml => toml::from_str(&content).map_err(|e| toml_error(path, &content, e)),
+            SourceKind::Yaml => serde_norway::from_str(&content).map_err(|e| yaml_error(path, e)),
+            _ => serde_json::from_value(value).map_err(|e| ConfigError::invalid(path, e)),
+        }?;
+        Ok((config, applied))
+    }
+
+    /// The configuration as JSON, whatever it's written in.
+    pub(crate) fn parse(&self, content: &str) -> Result<Value, ConfigError> {
+        let path = self.path.as_path();
+        match self.kind {
+            SourceKind::Json => {
+                serde_json::from_str(content).map_err(|e| ConfigError::invalid(path, e))
+            }
+            SourceKind::Toml => toml::from_str(content).map_err(|e| toml_error(path, content, e)),
+            SourceKind::Yaml => serde_norway::from_str(content).map_err(|e| yaml_error(path, e)),
+            SourceKind::CargoPackage | SourceKind::CargoWorkspace => {
+                let manifest: toml::Table =
+                    toml::from_str(content).map_err(|e| toml_error(path, content, e))?;
+                let section = self
+                    .cargo_section(&manifest)
+                    .ok_or_else(|| ConfigError::Missing(path.to_path_buf()))?;
+                serde_json::to_value(section).map_err(|e| ConfigError::invalid(path, e))
+            }
+            SourceKind::PackageJson => {
+                let mut package: Value =
+                    serde_json::from_str(content).map_err(|e| ConfigError::invalid(path, e))?;
+                package
+                    .get_mut("wtfm")
+                    .map(Value::take)
+                    .ok_or_else(|| ConfigError::Missing(path.to_path_buf()))
+            }
+        }
+    }
+
+    fn cargo_table(&self) -> &'static str {
+        match self.kind {
+            SourceKind::CargoWorkspace => "workspace",
+            _ => "package",
+        }
+    }
+
+    fn cargo_section<'a>(&self, manifest: &'a toml::Table) -> Option<&'a toml::Value> {
+        manifest
+            .get(self.cargo_table())?
+            .get("metadata")?
+            .get("wtfm")
+    }
+
+    /// Writes `config` back where it was read from.
+    pub fn save(&self, config: &WtfmConfig) -> Result<(), Box<dyn std::error::Error>> {
+        fs::write(&self.path, self.render(config)?)?;
+        Ok(())
+    }
+
+    /// The file `save` writes. Manifests keep their formatting, only the
+    /// wtfm section is replaced.
+    pub fn render(&self, config: &WtfmConfig) -> Result<String, Box<dyn std::error::Error>> {
+        Ok(match self.kind {
+            SourceKind::Json => config.to_json()?,
+            SourceKind::Toml => toml::to_string_pretty(config)?,
+            SourceKind::Yaml => serde_norway::to_string(config)?,
+            SourceKind::CargoPackage | SourceKind::CargoWorkspace => {
+                let mut manifest: toml_edit::DocumentMut =
+                    fs::read_to_string(&self.path)?.parse()?;
+                let section: toml_edit::DocumentMut = toml::to_string(config)?.parse()?;
+                let metadata = manifest[self.cargo_table()]["metadata"]
+                    .or_insert(toml_edit::table())
+                    .as_table_mut()
+                    .ok_or("`metadata` of Cargo.toml isn't a table")?;
+                metadata.set_implicit(true);
+                metadata.insert("wtfm", toml_edit::Item::Table(section.as_table().clone()));
+                manifest.to_string()
+            }
+            SourceKind::PackageJson => {
+                let package = fs::read_to_string(&self.path)?;
+                let (start, end) = version::find_top_level_value(&package, "wtfm")
+                    .ok_or("No \"wtfm\" key in package.json")?;
+                // nested one level deeper than the keys of package.json
+                let indentation = package[..start]
+                    .rsplit('\n')
+                    .next()
+                    .map(|line| line.len() - line.trim_start().len())
+                    .unwrap_or(2);
+                let json = config
+                    .to_json()?
+                    .replace('\n', &format!("\n{}", " ".repeat(indentation)));
+                format!("{}{}{}", &package[..start], json, &package[end..])
+            }
+        })
+    }
+}
+
+// The file name and the section, the project directory being implied
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let file = self
+            .path
+            .file_name()
+            .unwrap_or(self.path.as_os_str())
+            .to_string_lossy();
+        match self.kind {
+            SourceKind::Json | SourceKind::Toml | SourceKind::Yaml => write!(f, "{}", file),
+            SourceKind::CargoPackage | SourceKind::CargoWorkspace => {
+                write!(f, "{} [{}.metadata.wtfm]", file, self.cargo_table())
+            }
+            SourceKind::PackageJson => write!(f, "{} \"wtfm\"", file),
+        }
+    }
+}
+
+/// 1-based line and column of the byte at `offset` in `content`.
+pub(crate) fn line_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+    (line, column)
+}
+
+fn toml_error(path: &Path, content: &str, error: toml::de::Error) -> ConfigError {
+    let location = error.span().map(|span| line_column(content, span.start));
+    ConfigError::located(path, error.message().to_string(), location)
+}
+
+fn yaml_error(path: &Path, error: serde_norway::Error) -> ConfigError {
+    let location = error
+        .location()
+        .map(|location| (location.line(), location.column()));
+    ConfigError::located(path, error.to_string(), location)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const CONFIG: &str = r#"{
+  "schema_version": 2,
+  "project_name": "Demo",
+  "description": "A demo",
+  "version": "0.1.0",
+  "license": "MIT",
+  "setup_ci": false,
+  "author_quantity": 1,
+  "authors": [{ "name": "Jane Doe", "email": "jane@example.com" }],
+  "badges": ["crates-io"]
+}"#;
+
+    fn config() -> WtfmConfig {
+        serde_json::from_str(CONFIG).unwrap()
+    }
+
+    #[test]
+    fn test_find_by_precedence() {
+        let temp_dir = TempDir::new().unwrap();
+        let folder = temp_dir.path();
+        assert_eq!(ConfigSource::find(folder).kind, SourceKind::Json);
+
+        fs::write(
+            folder.join("package.json"),
+            "{\n  \"name\": \"demo\",\n  \"wtfm\": {}\n}\n",
+        )
+        .unwrap();
+        assert_eq!(ConfigSource::find(folder).kind, SourceKind::PackageJson);
+
+        // a manifest without a wtfm section doesn't count
+        fs::write(folder.join("Cargo.toml"), "[package]\nname = \"demo\"\n").unwrap();
+        assert_eq!(ConfigSource::find(folder).kind, SourceKind::PackageJson);
+        fs::write(
+            folder.join("Cargo.toml"),
+            "[workspace]\n\n[workspace.metadata.wtfm]\nproject_name = \"Demo\"\n",
+        )
+        .unwrap();
+        assert_eq!(ConfigSource::find(folder).kind, SourceKind::CargoWorkspace);
+
+        fs::write(folder.join(".wtfm.yaml"), "project_name: Demo\n").unwrap();
+        assert_eq!(ConfigSource::find(folder).kind, SourceKind::Yaml);
+        fs::write(folder.join(".wtfm.json"), CONFIG).unwrap();
+        assert_eq!(ConfigSource::find(folder).kind, SourceKind::Json);
+    }
+
+    #[test]
+    fn test_round_trip_every_source() {
+        let temp_dir = TempDir::new().unwrap();
+        let folder = temp_dir.path();
+        let cargo =
+            "# the demo\n[package]\nname = \"demo\" # keep me\n\n[dependencies]\nserde = \"1\"\n";
+        fs::write(folder.join("Cargo.toml"), cargo).unwrap();
+        fs::write(
+            folder.join("package.json"),
+            "{\n  \"name\": \"demo\",\n  \"wtfm\": {},\n  \"private\": true\n}\n",
+        )
+        .unwrap();
+
+        for source in ConfigSource::candidates(folder) {
+            if source.kind == SourceKind::CargoWorkspace {
+                continue;
+            }
+            source.save(&config()).unwrap();
+            let read = source.read().unwrap();
+            assert_eq!(read.project_name, "Demo", "{}", source);
+            assert_eq!(read.authors[0].email, "jane@example.com", "{}", source);
+            assert_eq!(read.content.badges.len(), 1, "{}", source);
+        }
+
+        let manifest = fs::read_to_string(folder.join("Cargo.toml")).unwrap();
+        assert!(manifest.starts_with("# the demo\n[package]\nname = \"demo\" # keep me\n"));
+        assert!(manifest.contains("[package.metadata.wtfm]\nschema_version = 2\n"));
+        assert!(
+            manifest.ends_with("[dependencies]\nserde = \"1\"\n"),
+            "{}",
+            manifest
+        );
+        let package = fs::read_to_string(folder.join("package.json")).unwrap();
+        assert!(package.contains("  \"wtfm\": {\n    \"schema_version\": 2,"));
+        assert!(package.ends_with("  },\n  \"private\": true\n}\n"));
+    }
+
+    #[test]
+    fn test_locate_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        let toml = temp_dir.path().join(".wtfm.toml");
+        fs::write(
+            &toml,
+            "schema_version = 2\nproject_name = \"Demo\"\nversion = \"one\"\n",
+        )
+        .unwrap();
+        let error = WtfmConfig::read(&toml).unwrap_err().to_string();
+        assert!(error.contains("at line 3, column 11"), "{}", error);
+
+        let yaml = temp_dir.path().join(".wtfm.yaml");
+        fs::write(&yaml, "schema_version: 2\nproject_name: [Demo\n").unwrap();
+        let error = WtfmConfig::read(&yaml).unwrap_err().to_string();
+        assert!(error.contains("at line"), "{}", error);
+    }
+}
@@ -1,16 +1,20 @@
+use crate::config::wtfm::{Author, ReadmeContent, WtfmConfig};
 use crate::licenses::{License, LicenseCategory};
-use dialoguer::{Input, Select};
+use crate::utils::badges::Badge;
+use dialoguer::{Input, MultiSelect, Select};
+use semver::Version;
 use std::fmt;
 
 #[derive(Debug)]
 pub struct WizardAnswers {
     pub project_name: String,
     pub description: String,
-    pub version: String,
+    pub version: Version,
     pub license: License,
     pub setup_ci: bool,
     pub author_quantity: u32,
-    pub authors: Vec<String>,
+    pub authors: Vec<Author>,
+    pub content: ReadmeContent,
 }
 
 impl fmt::Display for WizardAnswers {
@@ -24,18 +28,37 @@ impl fmt::Display for WizardAnswers {
             self.license.name,
             self.license.spdx_id,
             self.setup_ci,
-            self.authors.join(", ")
+            self.authors
+                .iter()
+                .map(Author::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
         )
     }
 }
 
+impl From<&WtfmConfig> for WizardAnswers {
+    fn from(config: &WtfmConfig) -> Self {
+        Self {
+            project_name: config.project_name.clone(),
+            description: config.description.clone(),
+            version: config.version.clone(),
+            license: config.license.clone(),
+            setup_ci: config.setup_ci,
+            author_quantity: config.author_quantity,
+            authors: config.authors.clone(),
+            content: config.content.clone(),
+        }
+    }
+}
+
 impl WizardAnswers {
     #[doc(hidden)]
     pub fn new_test() -> Self {
         Self {
             project_name: "Test Project".to_string(),
             description: "A test project".to_string(),
-            version: "0.1.0".to_string(),
+            version: Version::new(0, 1, 0),
             license: License::new(
                 "MIT",
                 "MIT License",
@@ -46,32 +69,52 @@ impl WizardAnswers {
             ),
             setup_ci: true,
             author_quantity: 2,
-            authors: vec!["Author 1".to_string(), "Author 2".to_string()],
+            authors: vec![Author::parse("Author 1"), Author::parse("Author 2")],
+            content: ReadmeContent::default(),
         }
     }
 
     pub fn from_interactive() -> Self {
-        let project_name: String = Input::new()
-            .with_prompt("Project name")
-            .interact()
-            .expect("Failed to get project name");
+        Self::from_interactive_with(&Self::default())
+    }
 
-        let description: String = Input::new()
-            .with_prompt("Description")
-            .interact()
-            .expect("Failed to get description");
+    /// Runs the wizard with `defaults` as the proposed answers, e.g. those
+    /// of an existing configuration.
+    pub fn from_interactive_with(defaults: &WizardAnswers) -> Self {
+        let project_name = input_with_default("Project name", &defaults.project_name);
+        let description = input_with_default("Description", &defaults.description);
 
         let version: String = Input::new()
             .with_prompt("Version")
-            .default("0.1.0".into())
+            .default(defaults.version.to_string())
+            .validate_with(|input: &String| -> Result<(), String> {
+                Version::parse(input).map(|_| ()).map_err(|e| {
+                    format!(
+                        "'{}' is not a semantic version (expected MAJOR.MINOR.PATCH, e.g. 1.2.3): {}",
+                        input, e
+                    )
+                })
+            })
             .interact()
             .expect("Failed to get version");
+        let version = Version::parse(&version).expect("Version was validated");
 
-        let available_licenses = License::get_licenses();
+        // A license outside the list, like an SPDX expression, is offered first
+        let mut available_licenses = License::get_licenses();
+        let default_license = match available_licenses
+            .iter()
+            .position(|license| license.spdx_id == defaults.license.spdx_id)
+        {
+            Some(index) => index,
+            None => {
+                available_licenses.insert(0, defaults.license.clone());
+                0
+            }
+        };
         let license_idx = Select::new()
             .with_prompt("Choose a license")
             .items(&available_licenses)
-            .default(0)
+            .default(default_license)
             .interact()
             .expect("Failed to get license choice");
 
@@ -80,26 +123,35 @@ impl WizardAnswers {
         let setup_ci = Select::new()
             .with_prompt("Setup CI?")
             .items(&["Yes", "No"])
-            .default(0)
+            .default(if defaults.setup_ci { 0 } else { 1 })
             .interact()
             .expect("Failed to get CI choice")
             == 0;
 
         let author_quantity: u32 = Input::new()
             .with_prompt("How many authors?")
-            .default(1)
+            .default(defaults.author_quantity)
             .interact()
             .expect("Failed to get authors quantity");
 
-        let authors = (0..author_quantity)
-            .map(|_| {
-                Input::new()
-                    .with_prompt("Author name")
-                    .interact()
-                    .expect("Failed to get author name")
-            })
+        let authors = (0..author_quantity as usize)
+            .map(|i| author_from_interactive(defaults.authors.get(i)))
             .collect();
 
+        // Existing content is kept unless it's described again
+        let content = if Select::new()
+            .with_prompt("Describe features, installation and usage now?")
+            .items(&["Yes", "No"])
+            .default(1)
+            .interact()
+            .expect("Failed to get content choice")
+            == 0
+        {
+            Self::content_from_interactive(&defaults.content)
+        } else {
+            defaults.content.clone()
+        };
+
         Self {
             project_name,
             description,
@@ -108,6 +160,142 @@ impl WizardAnswers {
             setup_ci,
             author_quantity,
             authors,
+            content,
+        }
+    }
+
+    /// Asks for the optional README sections. Every answer can be left empty.
+    fn content_from_interactive(defaults: &ReadmeContent) -> ReadmeContent {
+        let overview: Vec<String> = defaults
+            .overview
+            .iter()
+            .flat_map(|overview| overview.lines())
+            .map(String::from)
+            .collect();
+        let overview = input_lines("Overview, one line at a time", &overview).join("\n");
+        let screenshot = input_optional(
+            "Screenshot URL or path (optional)",
+            defaults.screenshot.as_deref(),
+        );
+        let demo = input_optional("Demo URL or path (optional)", defaults.demo.as_deref());
+        let badges = select_badges(&defaults.badges);
+
+        ReadmeContent {
+            overview: Some(overview).filter(|overview| !overview.is_empty()),
+            screenshot,
+            demo,
+            badge_version: defaults.badge_version,
+            badges,
+            features: input_lines("Feature", &defaults.features),
+            pre_requisites: input_lines("Prerequisite", &defaults.pre_requisites),
+            installation: input_lines("Installation step", &defaults.installation),
+            usage: input_lines("Usage example", &defaults.usage),
         }
     }
 }
+
+impl Default for WizardAnswers {
+    /// What the wizard proposes for a new project.
+    fn default() -> Self {
+        Self {
+            project_name: String::new(),
+            description: String::new(),
+            version: Version::new(0, 1, 0),
+            license: License::get_licenses().remove(0),
+            setup_ci: true,
+            author_quantity: 1,
+            authors: Vec::new(),
+            content: ReadmeContent::default(),
+        }
+    }
+}
+
+// Name, email, URL and role of an author, proposing those of `default`
+pub(crate) fn author_from_interactive(default: Option<&Author>) -> Author {
+    let name = input_with_default("Author name", default.map_or("", |author| &author.name));
+    let mut email = Input::<String>::new().with_prompt("Author email");
+    if let Some(default) = default.filter(|author| !author.email.is_empty()) {
+        email = email.default(default.email.clone());
+    }
+    let email = email
+        .validate_with(|input: &String| Author::check_email(input.trim()))
+        .interact()
+        .expect("Failed to get author email")
+        .trim()
+        .to_string();
+    let optional = |prompt: &str, default: Option<&String>| -> Option<String> {
+        let answer: String = Input::new()
+            .with_prompt(prompt)
+            .default(default.cloned().unwrap_or_default())
+            .allow_empty(true)
+            .interact()
+            .expect("Failed to get answer");
+        Some(answer.trim().to_string()).filter(|answer| !answer.is_empty())
+    };
+    Author {
+        name,
+        email,
+        url: optional(
+            "Author URL (optional)",
+            default.and_then(|author| author.url.as_ref()),
+        ),
+        role: optional(
+            "Author role, e.g. Maintainer (optional)",
+            default.and_then(|author| author.role.as_ref()),
+        ),
+    }
+}
+
+pub(crate) fn select_badges(defaults: &[Badge]) -> Vec<Badge> {
+    let titles: Vec<&str> = Badge::ALL.iter().map(Badge::title).collect();
+    let selected: Vec<bool> = Badge::ALL
+        .iter()
+        .map(|badge| defaults.contains(badge))
+        .collect();
+    MultiSelect::new()
+        .with_prompt("Badges to show (space to select)")
+        .items(&titles)
+        .defaults(&selected)
+        .interact()
+        .expect("Failed to get badges")
+        .into_iter()
+        .map(|i| Badge::ALL[i])
+        .collect()
+}
+
+// Required input, proposing `default` unless it's empty
+pub(crate) fn input_with_default(prompt: &str, default: &str) -> String {
+    let mut input = Input::<String>::new().with_prompt(prompt);
+    if !default.is_empty() {
+        input = input.default(default.to_string());
+    }
+    input
+        .interact()
+        .unwrap_or_else(|_| panic!("Failed to get {}", prompt.to_lowercase()))
+}
+
+// Optional input, proposing `default` which `-` removes
+fn input_optional(prompt: &str, default: Option<&str>) -> Option<String> {
+    let mut input = Input::<String>::new().allow_empty(true);
+    input = match default {
+        Some(default) => input
+            .with_prompt(format!("{} (- to remove)", prompt))
+            .default(default.to_string()),
+        None => input.with_prompt(prompt),
+    };
+    let answer = input.interact().expect("Failed to get answer");
+    Some(answer.trim().to_string()).filter(|answer| !answer.is_empty() && answer != "-")
+}
+
+// Multi-line input: the `defaults` one at a time, then one prompt per new
+// line until an empty one
+fn input_lines(prompt: &str, defaults: &[String]) -> Vec<String> {
+    let mut lines: Vec<String> = defaults
+        .iter()
+        .filter_map(|line| input_optional(prompt, Some(line)))
+        .collect();
+    while let Some(line) = input_optional(&format!("{} (empty to finish)", prompt), None) {
+        lines.push(line);
+    }
+    lines
+}
@@ -1,92 +1,506 @@
+use crate::config::global::GlobalConfig;
+use crate::config::source::ConfigSource;
+use crate::config::wizard::WizardAnswers;
 use crate::debug;
 use crate::licenses::License;
+use crate::utils::badges::Badge;
 use crate::utils::cargo::CargoInfo;
+use crate::utils::format::Format;
 use crate::utils::git::GitInfo;
-use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::Path;
+use crate::utils::template;
+use schemars::JsonSchema;
+use semver::Version;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct WtfmConfig {
+    /// JSON Schema editors check the file with, as written by
+    /// `wtfm config schema`
+    #[serde(rename = "$schema", default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    /// Layout of the file, see `migrate::SCHEMA_VERSION`
+    pub schema_version: u64,
     pub project_name: String,
     pub description: String,
-    pub version: String,
+    pub version: Version,
+    /// Written as its SPDX expression, e.g. `MIT OR Apache-2.0`
+    #[serde(with = "spdx_expression")]
+    #[schemars(with = "String")]
     pub license: License,
     pub setup_ci: bool,
     pub author_quantity: u32,
     pub authors: Vec<Author>,
+    #[serde(flatten)]
+    pub content: ReadmeContent,
     pub git_info: Option<GitInfo>,
     pub cargo_info: Option<CargoInfo>,
+    /// Directory of templates overriding the bundled ones for the README,
+    /// relative to the project directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template_dir: Option<PathBuf>,
+    /// Variant of the README, a built-in one like `minimal` or an entry
+    /// template of `template_dir`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub documents: Vec<Document>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<CliOption>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub docs: Option<DocsTree>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Optional README content, written as top-level keys of .wtfm.json.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ReadmeContent {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overview: Option<String>,
+    /// URL or path of a screenshot
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub screenshot: Option<String>,
+    /// URL or path of a demo, e.g. an animated GIF
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub demo: Option<String>,
+    /// Whether the README shows a version badge, before those in `badges`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub badge_version: bool,
+    /// Badges shown under the title, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub badges: Vec<Badge>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_requisites: Vec<String>,
+    /// Installation steps
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub installation: Vec<String>,
+    /// Usage examples
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub usage: Vec<String>,
+}
+
+impl ReadmeContent {
+    /// The badges to show, the version one first when `badge_version` is set.
+    pub fn badge_list(&self) -> Vec<Badge> {
+        let mut badges = self.badges.clone();
+        if self.badge_version && !badges.contains(&Badge::Version) {
+            badges.insert(0, Badge::Version);
+        }
+        badges
+    }
+
+    /// Whether there's anything for the Getting Started section.
+    pub fn has_getting_started(&self) -> bool {
+        !self.pre_requisites.is_empty() || !self.installation.is_empty() || !self.usage.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Author {
     pub name: String,
     pub email: String,
+    /// Homepage or profile
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Part in the project, e.g. `Maintainer`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
 }
 
-impl WtfmConfig {
-    pub fn load<P: AsRef<Path>>(path: P, _debug: bool) -> Option<Self> {
-        let config_content = match fs::read_to_string(path) {
-            Ok(content) => {
-                debug!("File content: {}", content);
-                content
-            }
-            Err(e) => {
-                debug!("Error reading config file: {}", e);
-                return None;
-            }
+impl Author {
+    /// Reads `Name <email> (url)`, the way Cargo, npm and git list authors.
+    /// Every part but the name is optional, the email is empty when there's
+    /// none.
+    pub fn parse(text: &str) -> Self {
+        let (rest, url) = match text.trim_end().strip_suffix(')') {
+            Some(rest) => match rest.rsplit_once('(') {
+                Some((rest, url)) => (rest, Some(url.trim().to_string())),
+                None => (text, None),
+            },
+            None => (text, None),
         };
+        let (name, email) = match rest.split_once('<') {
+            Some((name, email)) => (name, email.split('>').next().unwrap_or_default()),
+            None => (rest, ""),
+        };
+        Self {
+            name: name.trim().to_string(),
+            email: email.trim().to_string(),
+            url: url.filter(|url| !url.is_empty()),
+            role: None,
+        }
+    }
 
-        match serde_json::from_str(&config_content) {
-            Ok(config) => Some(config),
-            Err(e) => {
-                debug!("Error parsing config file: {}", e);
-                None
+    /// Checks the shape of an email address, `name@host.tld`.
+    pub fn check_email(email: &str) -> Result<(), String> {
+        let valid = match email.split_once('@') {
+            Some((user, host)) => {
+                !user.is_empty()
+                    && !host.contains('@')
+                    && host.split('.').count() > 1
+                    && host.split('.').all(|label| !label.is_empty())
+                    && !email
+                        .chars()
+                        .any(|c| c.is_whitespace() || c == '<' || c == '>')
             }
+            None => false,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(format!("'{}' is not an email address", email))
         }
     }
+}
 
-    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
-        let config_content = serde_json::to_string_pretty(self)?;
-        fs::write(path, config_content)
+impl fmt::Display for Author {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.email.is_empty() {
+            write!(f, " <{}>", self.email)?;
+        }
+        if let Some(url) = &self.url {
+            write!(f, " ({})", url)?;
+        }
+        Ok(())
     }
 }
 
-#[cfg(test)]
-mod tests {
+mod spdx_expression {
     use super::*;
-    use crate::licenses::{License, LicenseCategory};
-    use tempfile::NamedTempFile;
 
-    fn create_test_config() -> WtfmConfig {
-        WtfmConfig {
-            project_name: "Test".to_string(),
-            description: "Test Project".to_string(),
-            version: "0.1.0".to_string(),
+    pub fn serialize<S: Serializer>(license: &License, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&license.spdx_id)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<License, D::Error> {
+        let expression = String::deserialize(deserializer)?;
+        License::from_expression(&expression).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Why a configuration couldn't be read.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// There's no configuration file
+    Missing(PathBuf),
+    Unreadable(PathBuf, std::io::Error),
+    /// The file is there but it isn't a valid configuration. The location
+    /// is known when the error comes from the file as written.
+    Invalid {
+        path: PathBuf,
+        message: String,
+        line: Option<usize>,
+        column: Option<usize>,
+    },
+}
+
+impl ConfigError {
+    pub(crate) fn invalid(path: &Path, error: serde_json::Error) -> Self {
+        let location = (error.line() > 0).then(|| (error.line(), error.column()));
+        Self::located(path, error.to_string(), location)
+    }
+
+    /// An invalid configuration, `location` being the 1-based line and
+    /// column of the error.
+    pub(crate) fn located(path: &Path, message: String, location: Option<(usize, usize)>) -> Self {
+        // serde_json and serde_norway append the location to the message
+        let mut message = message;
+        if let Some((line, column)) = location {
+            let suffix = format!(" at line {} column {}", line, column);
+            if let Some(stripped) = message.strip_suffix(&suffix) {
+                message = stripped.to_string();
+            }
+        }
+        Self::Invalid {
+            path: path.to_path_buf(),
+            message,
+            line: location.map(|(line, _)| line),
+            column: location.map(|(_, column)| column),
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Missing(path) => {
+                write!(f, "No {} found, run `wtfm generate` first", path.display())
+            }
+            ConfigError::Unreadable(path, e) => {
+                write!(f, "Failed to read {}: {}", path.display(), e)
+            }
+            ConfigError::Invalid {
+                path,
+                message,
+                line,
+                column,
+            } => {
+                write!(f, "Invalid {}", path.display())?;
+                if let (Some(line), Some(column)) = (line, column) {
+                    write!(f, " at line {}, column {}", line, column)?;
+                }
+                write!(f, ": {}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// A command line option of the project, documented in its man page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CliOption {
+    /// Flags as shown to the user, e.g. `-o, --output <DIR>`
+    pub flags: String,
+    pub description: String,
+}
+
+/// A Diátaxis documentation tree created by `wtfm scaffold diataxis`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DocsTree {
+    /// Root of the tree, relative to the project directory
+    pub root: PathBuf,
+}
+
+/// A document rendered by `generate` in place of the default README.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Document {
+    /// Generated file, relative to the output directory
+    pub destination: PathBuf,
+    /// Directory of templates overriding the bundled ones, relative to the
+    /// project directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template_dir: Option<PathBuf>,
+    /// Entry point template inside `template_dir`
+    #[serde(default = "default_template")]
+    pub template: String,
+    #[serde(default, skip_serializing_if = "Format::is_markdown")]
+    pub format: Format,
+}
+
+fn default_template() -> String {
+    "readme.tera".to_string()
+}
+
+impl Document {
+    /// The README rendered with the bundled templates.
+    pub fn readme(destination: impl Into<PathBuf>, format: Format) -> Self {
+        Self {
+            destination: destination.into(),
+            template_dir: None,
+            template: default_template(),
+            format,
+        }
+    }
+}
+
+/// Community health files written by `generate --community`, with their
+/// entry template.
+pub const COMMUNITY_DOCUMENTS: [(&str, &str); 3] = [
+    ("CONTRIBUTING.md", "contributing.tera"),
+    ("CODE_OF_CONDUCT.md", "code_of_conduct.tera"),
+    ("SECURITY.md", "security.tera"),
+];
+
+/// Whether `document` is one of the community health files.
+pub fn is_community_document(document: &Document) -> bool {
+    COMMUNITY_DOCUMENTS
+        .iter()
+        .any(|(destination, _)| document.destination == Path::new(destination))
+}
+
+impl WtfmConfig {
+    /// Adds the community health files missing from the documents, so later
+    /// runs keep them up to date. The README, implied as long as no document
+    /// is declared, is declared first as `readme` in `format`, the file the
+    /// run renders. Returns whether anything was added.
+    pub fn track_community_documents(&mut self, readme: &Path, format: Format) -> bool {
+        let missing: Vec<Document> = COMMUNITY_DOCUMENTS
+            .iter()
+            .filter(|(destination, _)| {
+                !self
+                    .documents
+                    .iter()
+                    .any(|document| document.destination == Path::new(destination))
+            })
+            .map(|(destination, template)| Document {
+                destination: destination.into(),
+                template_dir: self.template_dir.clone(),
+                template: template.to_string(),
+                format: Format::Markdown,
+            })
+            .collect();
+        if missing.is_empty() {
+            return false;
+        }
+
+        if self.documents.is_empty() {
+            let readme = self.readme(readme, format);
+            self.documents.push(readme);
+        }
+        self.documents.extend(missing);
+        true
+    }
+
+    /// Takes the answers of the wizard, keeping the settings it doesn't ask
+    /// about.
+    pub fn apply_answers(&mut self, answers: &WizardAnswers) {
+        self.project_name = answers.project_name.clone();
+        self.description = answers.description.clone();
+        self.version = answers.version.clone();
+        self.license = answers.license.clone();
+        self.setup_ci = answers.setup_ci;
+        self.author_quantity = answers.author_quantity;
+        self.authors = answers.authors.clone();
+        self.content = answers.content.clone();
+    }
+
+    /// A configuration for tests, which change the settings they exercise.
+    #[cfg(test)]
+    pub(crate) fn new_test() -> Self {
+        Self {
+            schema: None,
+            schema_version: crate::config::migrate::SCHEMA_VERSION,
+            project_name: "Test Project".to_string(),
+            description: "Test Description".to_string(),
+            version: Version::new(0, 1, 0),
             license: License::new(
                 "MIT",
                 "MIT License",
-                LicenseCategory::Permissive,
+                crate::licenses::LicenseCategory::Permissive,
                 true,
                 true,
                 false,
             ),
-            setup_ci: true,
+            setup_ci: false,
             author_quantity: 1,
             authors: vec![Author {
                 name: "Test Author".to_string(),
                 email: "test@example.com".to_string(),
+                url: None,
+                role: None,
             }],
+            content: ReadmeContent::default(),
             git_info: None,
             cargo_info: None,
+            template_dir: None,
+            template: None,
+            documents: Vec::new(),
+            options: Vec::new(),
+            docs: None,
         }
     }
 
+    /// The README rendered with the template settings of the configuration.
+    pub fn readme(&self, destination: impl Into<PathBuf>, format: Format) -> Document {
+        Document {
+            template_dir: self.template_dir.clone(),
+            template: self
+                .template
+                .as_deref()
+                .map(template::template_file)
+                .unwrap_or_else(default_template),
+            ..Document::readme(destination, format)
+        }
+    }
+
+    /// The documents `generate` writes without `--file` or `--format`: those
+    /// declared, or the README alone.
+    pub fn generated_documents(&self) -> Vec<Document> {
+        if self.documents.is_empty() {
+            let format = Format::default();
+            vec![self.readme(format.default_file(), format)]
+        } else {
+            self.documents.clone()
+        }
+    }
+
+    /// The directories templates are read from before the bundled ones,
+    /// for the README and every declared document, relative to the project
+    /// directory.
+    pub fn template_dirs(&self) -> Vec<&Path> {
+        let mut dirs: Vec<&Path> = self
+            .template_dir
+            .iter()
+            .chain(
+                self.documents
+                    .iter()
+                    .filter_map(|d| d.template_dir.as_ref()),
+            )
+            .map(PathBuf::as_path)
+            .collect();
+        dirs.sort();
+        dirs.dedup();
+        dirs
+    }
+
+    /// The configuration at `path`, or `None` when it's missing or invalid.
+    pub fn load<P: AsRef<Path>>(path: P, _debug: bool) -> Option<Self> {
+        match Self::read(path) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                debug!("{}", e);
+                None
+            }
+        }
+    }
+
+    /// Reads the configuration at `path`, upgrading older layouts in memory.
+    /// The file is read as TOML or YAML after its extension, JSON otherwise.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        Self::read_migrated(path).map(|(config, _)| config)
+    }
+
+    /// Like `read`, also returning the migrations applied to the file.
+    pub fn read_migrated<P: AsRef<Path>>(
+        path: P,
+    ) -> Result<(Self, Vec<&'static str>), ConfigError> {
+        ConfigSource::file(path.as_ref()).read_migrated()
+    }
+
+    /// The configuration of the project in `project_folder` as written, with
+    /// the source it was read from, for commands that save it back.
+    /// See `ConfigSource::find` for the order in which sources are tried.
+    pub fn find(project_folder: &Path) -> Result<(Self, ConfigSource), ConfigError> {
+        let source = ConfigSource::find(project_folder);
+        let config = source.read()?;
+        Ok((config, source))
+    }
+
+    /// Like `find`, completed with the defaults of the user for rendering.
+    /// Never save the result, the defaults would end up in the project.
+    pub fn find_with_defaults(project_folder: &Path) -> Result<(Self, ConfigSource), ConfigError> {
+        let source = ConfigSource::find(project_folder);
+        let config = source.read_with(&GlobalConfig::load()?)?;
+        Ok((config, source))
+    }
+
+    /// Writes the configuration to `path`, in the format its extension
+    /// gives.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn std::error::Error>> {
+        ConfigSource::file(path.as_ref()).save(self)
+    }
+
+    /// The pretty-printed JSON `save` writes to disk.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::migrate::SCHEMA_VERSION;
+    use std::fs;
+    use tempfile::NamedTempFile;
+
     #[test]
     fn test_save_and_load_config() {
         let temp_file = NamedTempFile::new().unwrap();
-        let config = create_test_config();
+        let config = WtfmConfig::new_test();
 
         config.save(temp_file.path()).unwrap();
         let loaded_config = WtfmConfig::load(temp_file.path(), false).unwrap();
@@ -94,4 +508,114 @@ mod tests {
         assert_eq!(loaded_config.project_name, config.project_name);
         assert_eq!(loaded_config.license.spdx_id, config.license.spdx_id);
     }
+
+    #[test]
+    fn test_track_community_documents() {
+        let mut config = WtfmConfig::new_test();
+        assert!(config.track_community_documents(Path::new("README.adoc"), Format::Asciidoc));
+        let destinations: Vec<&Path> = config
+            .documents
+            .iter()
+            .map(|document| document.destination.as_path())
+            .collect();
+        assert_eq!(
+            destinations,
+            vec![
+                Path::new("README.adoc"),
+                Path::new("CONTRIBUTING.md"),
+                Path::new("CODE_OF_CONDUCT.md"),
+                Path::new("SECURITY.md")
+            ]
+        );
+        assert_eq!(config.documents[0].format, Format::Asciidoc);
+        assert!(!config.track_community_documents(Path::new("README.md"), Format::Markdown));
+    }
+
+    #[test]
+    fn test_load_rejects_invalid_version() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut config = serde_json::to_value(WtfmConfig::new_test()).unwrap();
+        config["version"] = serde_json::Value::from("one point oh");
+        fs::write(temp_file.path(), config.to_string()).unwrap();
+
+        assert!(WtfmConfig::load(temp_file.path(), false).is_none());
+    }
+
+    #[test]
+    fn test_parse_author() {
+        let author = Author::parse("Jane Doe <jane@example.com> (https://jane.dev)");
+        assert_eq!(author.name, "Jane Doe");
+        assert_eq!(author.email, "jane@example.com");
+        assert_eq!(author.url.as_deref(), Some("https://jane.dev"));
+        assert_eq!(
+            author.to_string(),
+            "Jane Doe <jane@example.com> (https://jane.dev)"
+        );
+        for text in ["Jane", "Jane <", "Jane <jane@example.com", "<>", "()", ""] {
+            let author = Author::parse(text);
+            assert_eq!(Author::parse(&author.to_string()), author, "{}", text);
+        }
+        assert_eq!(
+            Author::parse("Jane <jane@example.com").email,
+            "jane@example.com"
+        );
+
+        assert!(Author::check_email("jane@example.com").is_ok());
+        for invalid in [
+            "",
+            "jane",
+            "jane@",
+            "@example.com",
+            "jane@localhost",
+            "ja ne@x.io",
+        ] {
+            assert!(Author::check_email(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_read_reports_missing_and_invalid() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join(".wtfm.json");
+        assert!(matches!(
+            WtfmConfig::read(&path),
+            Err(ConfigError::Missing(_))
+        ));
+
+        let json = WtfmConfig::new_test().to_json().unwrap();
+        fs::write(&path, json.replace("\"Test Author\"", "42")).unwrap();
+        let error = WtfmConfig::read(&path).unwrap_err();
+        let ConfigError::Invalid { line, .. } = &error else {
+            panic!("{}", error);
+        };
+        assert!(line.is_some());
+        assert!(error.to_string().contains("invalid type: integer `42`"));
+    }
+
+    #[test]
+    fn test_read_migrates_first_layout() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut config = serde_json::to_value(WtfmConfig::new_test()).unwrap();
+        let map = config.as_object_mut().unwrap();
+        map.remove("schema_version");
+        map.insert(
+            "license".to_string(),
+            serde_json::json!({ "spdx_id": "MIT OR Apache-2.0", "name": "MIT or Apache" }),
+        );
+        map.insert(
+            "authors".to_string(),
+            serde_json::json!(["Jane Doe <jane@example.com>"]),
+        );
+        fs::write(temp_file.path(), config.to_string()).unwrap();
+
+        let (config, applied) = WtfmConfig::read_migrated(temp_file.path()).unwrap();
+        assert_eq!(applied.len(), 2);
+        assert_eq!(config.schema_version, SCHEMA_VERSION);
+        assert_eq!(config.license.spdx_id, "MIT OR Apache-2.0");
+        assert_eq!(config.authors[0].email, "jane@example.com");
+        assert!(config
+            .to_json()
+            .unwrap()
+            .contains("\"license\": \"MIT OR Apache-2.0\""));
+    }
 }
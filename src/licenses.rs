@@ -67,10 +67,206 @@ impl License {
                 true,
                 false,
             ),
+            License::new(
+                "GPL-3.0-or-later",
+                "GNU General Public License v3.0 or later",
+                LicenseCategory::Copyleft,
+                true,
+                true,
+                false,
+            ),
+            License::new(
+                "GPL-2.0-only",
+                "GNU General Public License v2.0 only",
+                LicenseCategory::Copyleft,
+                true,
+                true,
+                false,
+            ),
+            License::new(
+                "GPL-2.0-or-later",
+                "GNU General Public License v2.0 or later",
+                LicenseCategory::Copyleft,
+                true,
+                true,
+                false,
+            ),
+            License::new(
+                "AGPL-3.0-only",
+                "GNU Affero General Public License v3.0 only",
+                LicenseCategory::Copyleft,
+                true,
+                true,
+                false,
+            ),
+            License::new(
+                "AGPL-3.0-or-later",
+                "GNU Affero General Public License v3.0 or later",
+                LicenseCategory::Copyleft,
+                true,
+                true,
+                false,
+            ),
+            License::new(
+                "LGPL-3.0-only",
+                "GNU Lesser General Public License v3.0 only",
+                LicenseCategory::WeakCopyleft,
+                true,
+                true,
+                false,
+            ),
+            License::new(
+                "LGPL-3.0-or-later",
+                "GNU Lesser General Public License v3.0 or later",
+                LicenseCategory::WeakCopyleft,
+                true,
+                true,
+                false,
+            ),
+            License::new(
+                "MPL-2.0",
+                "Mozilla Public License 2.0",
+                LicenseCategory::WeakCopyleft,
+                true,
+                true,
+                false,
+            ),
+            License::new(
+                "BSD-2-Clause",
+                "BSD 2-Clause \"Simplified\" License",
+                LicenseCategory::Permissive,
+                true,
+                true,
+                false,
+            ),
+            License::new(
+                "BSD-3-Clause",
+                "BSD 3-Clause \"New\" or \"Revised\" License",
+                LicenseCategory::Permissive,
+                true,
+                true,
+                false,
+            ),
+            License::new(
+                "ISC",
+                "ISC License",
+                LicenseCategory::Permissive,
+                true,
+                true,
+                false,
+            ),
+            License::new(
+                "Unlicense",
+                "The Unlicense",
+                LicenseCategory::PublicDomain,
+                true,
+                true,
+                false,
+            ),
+            License::new(
+                "CC0-1.0",
+                "Creative Commons Zero v1.0 Universal",
+                LicenseCategory::PublicDomain,
+                false,
+                true,
+                false,
+            ),
+            License::new(
+                "CC-BY-4.0",
+                "Creative Commons Attribution 4.0 International",
+                LicenseCategory::Creative,
+                false,
+                true,
+                false,
+            ),
             // Add more licenses...
         ]
     }
 
+    /// The license with the SPDX identifier `spdx_id`, if it's a known one.
+    pub fn find(spdx_id: &str) -> Option<License> {
+        Self::get_licenses()
+            .into_iter()
+            .find(|license| license.spdx_id.eq_ignore_ascii_case(spdx_id))
+            .map(|license| License {
+                url: Some(format!(
+                    "https://spdx.org/licenses/{}.html",
+                    license.spdx_id
+                )),
+                ..license
+            })
+    }
+
+    /// Reads an SPDX license expression such as `MIT OR Apache-2.0`. A single
+    /// known identifier gives that license, anything else a license named
+    /// after its parts. Identifiers missing from the list are kept as they
+    /// are, so `LicenseRef-` ones work too.
+    pub fn from_expression(expression: &str) -> Result<License, String> {
+        let spaced = expression.replace('(', " ( ").replace(')', " ) ");
+        let tokens: Vec<&str> = spaced.split_whitespace().collect();
+        let mut licenses = Vec::new();
+        let mut name = Vec::new();
+        let mut expect_id = true;
+        let mut depth = 0i32;
+        for (i, token) in tokens.iter().enumerate() {
+            match *token {
+                "(" if expect_id => {
+                    depth += 1;
+                    name.push("(".to_string());
+                }
+                ")" if !expect_id => {
+                    depth -= 1;
+                    name.push(")".to_string());
+                }
+                "AND" | "OR" | "WITH" if !expect_id => {
+                    expect_id = true;
+                    name.push(token.to_lowercase());
+                }
+                id if expect_id && !matches!(id, "(" | ")" | "AND" | "OR" | "WITH") => {
+                    expect_id = false;
+                    // the exception after WITH is not a license
+                    if i > 0 && tokens[i - 1] == "WITH" {
+                        name.push(id.to_string());
+                        continue;
+                    }
+                    let license = Self::find(id).unwrap_or_else(|| {
+                        License::new(id, id, LicenseCategory::Permissive, false, false, false)
+                    });
+                    name.push(license.name.clone());
+                    licenses.push(license);
+                }
+                _ => {
+                    return Err(format!(
+                        "Unexpected `{}` in license expression `{}`",
+                        token, expression
+                    ))
+                }
+            }
+            if depth < 0 {
+                return Err(format!(
+                    "Unbalanced parentheses in license expression `{}`",
+                    expression
+                ));
+            }
+        }
+        if expect_id || depth != 0 {
+            return Err(format!("Incomplete license expression `{}`", expression));
+        }
+
+        if tokens.len() == 1 {
+            return Ok(licenses.remove(0));
+        }
+        Ok(License {
+            spdx_id: tokens.join(" ").replace("( ", "(").replace(" )", ")"),
+            name: name.join(" ").replace("( ", "(").replace(" )", ")"),
+            category: licenses[0].category.clone(),
+            osi_approved: licenses.iter().all(|license| license.osi_approved),
+            fsf_libre: licenses.iter().all(|license| license.fsf_libre),
+            deprecated: licenses.iter().any(|license| license.deprecated),
+            url: None,
+        })
+    }
+
     pub fn get_active_licenses() -> Vec<License> {
         Self::get_licenses()
             .into_iter()
@@ -122,6 +318,33 @@ mod tests {
         assert_eq!(license.url, None);
     }
 
+    #[test]
+    fn test_from_expression() {
+        let mit = License::from_expression("MIT").unwrap();
+        assert_eq!(mit.name, "MIT License");
+        assert_eq!(
+            mit.url.as_deref(),
+            Some("https://spdx.org/licenses/MIT.html")
+        );
+
+        let dual = License::from_expression("MIT OR (Apache-2.0 AND LicenseRef-Custom)").unwrap();
+        assert_eq!(dual.spdx_id, "MIT OR (Apache-2.0 AND LicenseRef-Custom)");
+        assert_eq!(
+            dual.name,
+            "MIT License or (Apache License 2.0 and LicenseRef-Custom)"
+        );
+        assert!(!dual.osi_approved);
+
+        let exception =
+            License::from_expression("GPL-2.0-or-later WITH Classpath-exception-2.0").unwrap();
+        assert!(exception.osi_approved);
+
+        assert!(License::from_expression("").is_err());
+        assert!(License::from_expression("MIT OR").is_err());
+        assert!(License::from_expression("(MIT").is_err());
+        assert!(License::from_expression("MIT Apache-2.0").is_err());
+    }
+
     #[test]
     fn test_filter_methods() {
         let licenses = License::get_licenses();
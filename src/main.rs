@@ -9,8 +9,16 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     match &cli.command {
         Some(Commands::Author) => commands::author::execute(&Commands::Author, cli.debug),
+        Some(cmd @ Commands::Check { .. }) => commands::check::execute(cmd, cli.debug),
+        Some(cmd @ Commands::Config { .. }) => commands::config::execute(cmd, cli.debug),
         Some(cmd @ Commands::Generate { .. }) => commands::generate::execute(cmd, cli.debug),
         Some(cmd @ Commands::Analyze { .. }) => commands::analyze::execute(cmd, cli.debug),
+        Some(cmd @ Commands::Man { .. }) => commands::man::execute(cmd, cli.debug),
+        Some(cmd @ Commands::Scaffold { .. }) => commands::scaffold::execute(cmd, cli.debug),
+        Some(cmd @ Commands::Serve { .. }) => commands::serve::execute(cmd, cli.debug),
+        Some(cmd @ Commands::Site { .. }) => commands::site::execute(cmd, cli.debug),
+        Some(cmd @ Commands::Templates { .. }) => commands::templates::execute(cmd, cli.debug),
+        Some(cmd @ Commands::Version { .. }) => commands::version::execute(cmd, cli.debug),
         None => commands::analyze::execute(
             &Commands::Analyze {
                 project_folder: std::path::PathBuf::from("."),
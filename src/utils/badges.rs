@@ -0,0 +1,336 @@
+use crate::config::wizard::WizardAnswers;
+use crate::utils::cargo::Cargo;
+use crate::utils::git::{self, Forge, Git};
+use crate::utils::html;
+use crate::utils::node::Node;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Badges a README can show, listed in `"badges"` of .wtfm.json.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum Badge {
+    /// Version of the project, from .wtfm.json
+    Version,
+    CratesIo,
+    DocsRs,
+    /// Status of the CI workflow on the default branch
+    Ci,
+    License,
+    /// Minimum supported Rust version, from `rust-version` in Cargo.toml
+    Msrv,
+    /// Downloads from crates.io
+    Downloads,
+    Npm,
+    Coverage,
+}
+
+impl Badge {
+    pub const ALL: [Badge; 9] = [
+        Badge::Version,
+        Badge::CratesIo,
+        Badge::DocsRs,
+        Badge::Ci,
+        Badge::License,
+        Badge::Msrv,
+        Badge::Downloads,
+        Badge::Npm,
+        Badge::Coverage,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Badge::Version => "Version",
+            Badge::CratesIo => "Crates.io",
+            Badge::DocsRs => "Docs.rs",
+            Badge::Ci => "CI",
+            Badge::License => "License",
+            Badge::Msrv => "MSRV",
+            Badge::Downloads => "Downloads",
+            Badge::Npm => "npm",
+            Badge::Coverage => "Coverage",
+        }
+    }
+}
+
+/// What the badges are built from: the answers and, when the project is at
+/// hand, its manifests and git remote.
+#[derive(Debug, Clone, Default)]
+pub struct Source {
+    pub version: String,
+    /// SPDX identifier of the license
+    pub license: Option<String>,
+    pub license_url: Option<String>,
+    pub crate_name: Option<String>,
+    pub rust_version: Option<String>,
+    pub npm_name: Option<String>,
+    /// Web address of the repository
+    pub repository: Option<String>,
+    pub branch: Option<String>,
+}
+
+impl Source {
+    pub fn new(answers: &WizardAnswers) -> Self {
+        Self {
+            version: answers.version.to_string(),
+            license: Some(answers.license.spdx_id.clone()),
+            license_url: answers.license.url.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Completes the answers with Cargo.toml, package.json and the git remote
+    /// of the project in `project_folder`.
+    pub fn detect(answers: &WizardAnswers, project_folder: &Path) -> Self {
+        let mut source = Self::new(answers);
+
+        let cargo = Cargo::new(project_folder);
+        let git = Git::new(project_folder).info();
+        if let Some(info) = cargo.info() {
+            source.crate_name = info.package_name.clone();
+            source.rust_version = info.rust_version.clone();
+            source.repository = info.repository.as_deref().and_then(git::web_url);
+        }
+        if let Some(info) = Node::new(project_folder).info() {
+            source.npm_name = info.package_name.clone();
+        }
+        if let Some(info) = git {
+            // the remote is the actual repository, the manifest may be stale
+            if let Some(url) = info.remote_url.as_deref().and_then(git::web_url) {
+                source.repository = Some(url);
+            }
+            source.branch = info.current_branch;
+        }
+        source
+    }
+
+    fn branch(&self) -> &str {
+        self.branch.as_deref().unwrap_or("main")
+    }
+}
+
+/// A badge ready to be put in a document, in Markdown and HTML.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BadgeImage {
+    pub alt: String,
+    pub image: String,
+    pub link: Option<String>,
+    pub markdown: String,
+    pub html: String,
+}
+
+impl BadgeImage {
+    fn new(badge: Badge, image: String, link: Option<String>) -> Self {
+        let alt = badge.title().to_string();
+        let markdown = match &link {
+            Some(link) => format!("[![{}]({})]({})", alt, image, link),
+            None => format!("![{}]({})", alt, image),
+        };
+        let img = format!(
+            "<img src=\"{}\" alt=\"{}\">",
+            html::escape(&image),
+            html::escape(&alt)
+        );
+        let html = match &link {
+            Some(link) => format!("<a href=\"{}\">{}</a>", html::escape(link), img),
+            None => img,
+        };
+        Self {
+            alt,
+            image,
+            link,
+            markdown,
+            html,
+        }
+    }
+}
+
+/// Builds `badges` in order, skipping those `source` lacks the data for.
+pub fn build(badges: &[Badge], source: &Source) -> Vec<BadgeImage> {
+    let mut built: Vec<BadgeImage> = Vec::new();
+    for badge in badges {
+        if let Some(image) = build_badge(*badge, source) {
+            if !built.contains(&image) {
+                built.push(image);
+            }
+        }
+    }
+    built
+}
+
+fn build_badge(badge: Badge, source: &Source) -> Option<BadgeImage> {
+    let crate_name = source.crate_name.as_deref();
+    let repository = source.repository.as_deref();
+    let (image, link) = match badge {
+        Badge::Version => (static_badge("version", &source.version, "blue"), None),
+        Badge::CratesIo => {
+            let name = crate_name?;
+            (
+                format!("https://img.shields.io/crates/v/{}.svg", name),
+                Some(format!("https://crates.io/crates/{}", name)),
+            )
+        }
+        Badge::DocsRs => {
+            let name = crate_name?;
+            (
+                format!("https://docs.rs/{}/badge.svg", name),
+                Some(format!("https://docs.rs/{}", name)),
+            )
+        }
+        Badge::Ci => ci_badge(repository?, source.branch())?,
+        Badge::License => (
+            static_badge("license", source.license.as_deref()?, "blue"),
+            source.license_url.clone(),
+        ),
+        Badge::Msrv => (
+            static_badge("MSRV", source.rust_version.as_deref()?, "orange"),
+            None,
+        ),
+        Badge::Downloads => {
+            let name = crate_name?;
+            (
+                format!("https://img.shields.io/crates/d/{}.svg", name),
+                Some(format!("https://crates.io/crates/{}", name)),
+            )
+        }
+        Badge::Npm => {
+            let name = source.npm_name.as_deref()?;
+            (
+                format!("https://img.shields.io/npm/v/{}.svg", name),
+                Some(format!("https://www.npmjs.com/package/{}", name)),
+            )
+        }
+        Badge::Coverage => coverage_badge(repository?, source.branch())?,
+    };
+    Some(BadgeImage::new(badge, image, link))
+}
+
+// Status of the `ci.yml` workflow, or of the pipeline on GitLab
+fn ci_badge(repository: &str, branch: &str) -> Option<(String, Option<String>)> {
+    match Forge::from_url(repository)? {
+        Forge::GitHub => Some((
+            format!("{}/actions/workflows/ci.yml/badge.svg", repository),
+            Some(format!("{}/actions/workflows/ci.yml", repository)),
+        )),
+        Forge::GitLab => Some((
+            format!("{}/badges/{}/pipeline.svg", repository, branch),
+            Some(format!("{}/-/pipelines", repository)),
+        )),
+        Forge::Codeberg => Some((
+            format!("{}/badges/workflows/ci.yml/badge.svg", repository),
+            Some(format!("{}/actions", repository)),
+        )),
+        Forge::Bitbucket => None,
+    }
+}
+
+// GitLab reports coverage itself, other forges go through Codecov
+fn coverage_badge(repository: &str, branch: &str) -> Option<(String, Option<String>)> {
+    let service = match Forge::from_url(repository)? {
+        Forge::GitLab => {
+            return Some((
+                format!("{}/badges/{}/coverage.svg", repository, branch),
+                Some(format!("{}/-/commits/{}", repository, branch)),
+            ))
+        }
+        Forge::GitHub => "gh",
+        Forge::Bitbucket => "bb",
+        Forge::Codeberg => return None,
+    };
+    let path = repository.splitn(4, '/').nth(3)?;
+    Some((
+        format!(
+            "https://codecov.io/{}/{}/branch/{}/graph/badge.svg",
+            service, path, branch
+        ),
+        Some(format!("https://codecov.io/{}/{}", service, path)),
+    ))
+}
+
+// shields.io static badge, where dashes and underscores must be doubled
+fn static_badge(label: &str, message: &str, color: &str) -> String {
+    let escape = |text: &str| {
+        text.replace('-', "--")
+            .replace('_', "__")
+            .replace(' ', "%20")
+    };
+    format!(
+        "https://img.shields.io/badge/{}-{}-{}.svg",
+        escape(label),
+        escape(message),
+        color
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source() -> Source {
+        Source {
+            version: "0.1.0".to_string(),
+            license: Some("Apache-2.0".to_string()),
+            license_url: None,
+            crate_name: Some("wtfm".to_string()),
+            rust_version: Some("1.75".to_string()),
+            npm_name: None,
+            repository: Some("https://github.com/Solexma/wtfm".to_string()),
+            branch: None,
+        }
+    }
+
+    #[test]
+    fn test_build_in_order() {
+        let badges = build(
+            &[
+                Badge::CratesIo,
+                Badge::Npm,
+                Badge::Ci,
+                Badge::License,
+                Badge::Msrv,
+            ],
+            &source(),
+        );
+        let markdown: Vec<&str> = badges.iter().map(|badge| badge.markdown.as_str()).collect();
+        assert_eq!(
+            markdown,
+            vec![
+                "[![Crates.io](https://img.shields.io/crates/v/wtfm.svg)](https://crates.io/crates/wtfm)",
+                "[![CI](https://github.com/Solexma/wtfm/actions/workflows/ci.yml/badge.svg)](https://github.com/Solexma/wtfm/actions/workflows/ci.yml)",
+                "![License](https://img.shields.io/badge/license-Apache--2.0-blue.svg)",
+                "![MSRV](https://img.shields.io/badge/MSRV-1.75-orange.svg)",
+            ]
+        );
+        assert_eq!(
+            badges[0].html,
+            "<a href=\"https://crates.io/crates/wtfm\"><img src=\"https://img.shields.io/crates/v/wtfm.svg\" alt=\"Crates.io\"></a>"
+        );
+    }
+
+    #[test]
+    fn test_forge_aware_badges() {
+        let mut source = source();
+        source.repository = Some("https://gitlab.com/solexma/wtfm".to_string());
+        source.branch = Some("develop".to_string());
+        let badges = build(&[Badge::Ci, Badge::Coverage], &source);
+        assert_eq!(
+            badges[0].image,
+            "https://gitlab.com/solexma/wtfm/badges/develop/pipeline.svg"
+        );
+        assert_eq!(
+            badges[1].image,
+            "https://gitlab.com/solexma/wtfm/badges/develop/coverage.svg"
+        );
+
+        source.repository = Some("https://github.com/Solexma/wtfm".to_string());
+        let coverage = build(&[Badge::Coverage], &source);
+        assert_eq!(
+            coverage[0].image,
+            "https://codecov.io/gh/Solexma/wtfm/branch/develop/graph/badge.svg"
+        );
+
+        source.repository = None;
+        assert!(build(&[Badge::Ci, Badge::Coverage], &source).is_empty());
+    }
+}
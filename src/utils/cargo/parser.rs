@@ -9,8 +9,12 @@ pub(crate) fn parse_cargo_info(cargo_path: &Path) -> CargoInfo {
     let authors = read_authors(cargo_path).unwrap_or_default();
     let description = read_description(cargo_path);
     let repository = read_repository(cargo_path);
+    let license = read_license(cargo_path);
     let keywords = read_keywords(cargo_path).unwrap_or_default();
     let categories = read_categories(cargo_path).unwrap_or_default();
+    let bins = read_bins(cargo_path, package_name.as_deref());
+    let features = read_features(cargo_path);
+    let rust_version = read_rust_version(cargo_path);
 
     CargoInfo {
         package_name,
@@ -18,8 +22,12 @@ pub(crate) fn parse_cargo_info(cargo_path: &Path) -> CargoInfo {
         authors,
         description,
         repository,
+        license,
         keywords,
         categories,
+        bins,
+        features,
+        rust_version,
     }
 }
 
@@ -52,8 +60,13 @@ fn read_authors(cargo_path: &Path) -> Option<Vec<String>> {
     cargo_toml
         .get("package")?
         .get("authors")?
-        .as_str()
-        .map(|s| s.split(',').map(String::from).collect())
+        .as_array()
+        .map(|authors| {
+            authors
+                .iter()
+                .filter_map(|author| author.as_str().map(String::from))
+                .collect()
+        })
 }
 
 fn read_description(cargo_path: &Path) -> Option<String> {
@@ -78,6 +91,17 @@ fn read_repository(cargo_path: &Path) -> Option<String> {
         .map(String::from)
 }
 
+fn read_license(cargo_path: &Path) -> Option<String> {
+    let content = fs::read_to_string(cargo_path).ok()?;
+    let cargo_toml: toml::Value = content.parse().ok()?;
+
+    cargo_toml
+        .get("package")?
+        .get("license")?
+        .as_str()
+        .map(String::from)
+}
+
 fn read_keywords(cargo_path: &Path) -> Option<Vec<String>> {
     let content = fs::read_to_string(cargo_path).ok()?;
     let cargo_toml: toml::Value = content.parse().ok()?;
@@ -99,3 +123,68 @@ fn read_categories(cargo_path: &Path) -> Option<Vec<String>> {
         .as_str()
         .map(|s| s.split(',').map(String::from).collect())
 }
+
+/// Lists the binary targets: the explicit `[[bin]]` tables, or the targets
+/// Cargo discovers on its own from `src/main.rs` and `src/bin/*.rs`.
+fn read_bins(cargo_path: &Path, package_name: Option<&str>) -> Vec<String> {
+    let declared: Vec<String> = fs::read_to_string(cargo_path)
+        .ok()
+        .and_then(|content| content.parse::<toml::Value>().ok())
+        .and_then(|cargo_toml| cargo_toml.get("bin")?.as_array().cloned())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|bin| bin.get("name")?.as_str().map(String::from))
+        .collect();
+    if !declared.is_empty() {
+        return declared;
+    }
+
+    let src = cargo_path.parent().unwrap_or(Path::new(".")).join("src");
+    let mut bins = Vec::new();
+    if let Some(name) = package_name.filter(|_| src.join("main.rs").exists()) {
+        bins.push(name.to_string());
+    }
+    if let Ok(entries) = fs::read_dir(src.join("bin")) {
+        let mut discovered: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let path = entry.path();
+                if path.extension()? == "rs" {
+                    Some(path.file_stem()?.to_string_lossy().to_string())
+                } else if path.join("main.rs").exists() {
+                    Some(path.file_name()?.to_string_lossy().to_string())
+                } else {
+                    None
+                }
+            })
+            .collect();
+        discovered.sort();
+        bins.extend(discovered);
+    }
+    bins
+}
+
+/// The minimum supported Rust version, `package.rust-version`.
+fn read_rust_version(cargo_path: &Path) -> Option<String> {
+    let content = fs::read_to_string(cargo_path).ok()?;
+    let cargo_toml: toml::Value = content.parse().ok()?;
+
+    cargo_toml
+        .get("package")?
+        .get("rust-version")?
+        .as_str()
+        .map(String::from)
+}
+
+/// Lists the features declared in the `[features]` table, `default` aside.
+fn read_features(cargo_path: &Path) -> Vec<String> {
+    fs::read_to_string(cargo_path)
+        .ok()
+        .and_then(|content| content.parse::<toml::Value>().ok())
+        .and_then(|cargo_toml| cargo_toml.get("features")?.as_table().cloned())
+        .unwrap_or_default()
+        .keys()
+        .filter(|name| name.as_str() != "default")
+        .cloned()
+        .collect()
+}
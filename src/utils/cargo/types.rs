@@ -1,16 +1,25 @@
 use super::parser;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct CargoInfo {
     pub package_name: Option<String>,
     pub version: Option<String>,
     pub authors: Vec<String>,
     pub description: Option<String>,
     pub repository: Option<String>,
+    pub license: Option<String>,
     pub keywords: Vec<String>,
     pub categories: Vec<String>,
+    #[serde(default)]
+    pub bins: Vec<String>,
+    #[serde(default)]
+    pub features: Vec<String>,
+    /// Minimum supported Rust version
+    #[serde(default)]
+    pub rust_version: Option<String>,
 }
 
 pub struct Cargo {
@@ -0,0 +1,318 @@
+use crate::utils::git::Forge;
+use crate::utils::node::Node;
+use crate::utils::template;
+use serde::Serialize;
+use std::path::Path;
+use tera::Context;
+
+/// Bundled templates of the CI configurations, see `template::load`.
+pub const TEMPLATE_SET: &str = "ci";
+
+/// Languages whose tooling the pipeline runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ecosystem {
+    Rust,
+    Node,
+    Python,
+}
+
+impl Ecosystem {
+    /// Ecosystems of the project in `project_folder`, from its manifests.
+    pub fn detect(project_folder: &Path) -> Vec<Ecosystem> {
+        let has = |file: &str| project_folder.join(file).is_file();
+        let mut ecosystems = Vec::new();
+        if has("Cargo.toml") {
+            ecosystems.push(Ecosystem::Rust);
+        }
+        if has("package.json") {
+            ecosystems.push(Ecosystem::Node);
+        }
+        if has("pyproject.toml") || has("setup.py") || has("requirements.txt") {
+            ecosystems.push(Ecosystem::Python);
+        }
+        ecosystems
+    }
+
+    /// Command building the project from a fresh clone.
+    pub fn build_command(&self) -> &'static str {
+        match self {
+            Ecosystem::Rust => "cargo build",
+            Ecosystem::Node => "npm install",
+            Ecosystem::Python => "pip install -e .",
+        }
+    }
+}
+
+/// The CI service running the pipeline, which depends on the forge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum System {
+    GitHubActions,
+    GitLabCi,
+    ForgejoActions,
+}
+
+impl System {
+    /// GitHub Actions is the default when the forge is unknown.
+    pub fn for_forge(forge: Option<Forge>) -> Self {
+        match forge {
+            Some(Forge::GitLab) => System::GitLabCi,
+            Some(Forge::Codeberg) => System::ForgejoActions,
+            _ => System::GitHubActions,
+        }
+    }
+
+    /// Path of the configuration, relative to the project directory.
+    pub fn path(&self) -> &'static str {
+        match self {
+            System::GitHubActions => ".github/workflows/ci.yml",
+            System::GitLabCi => ".gitlab-ci.yml",
+            System::ForgejoActions => ".forgejo/workflows/ci.yml",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            System::GitHubActions => "GitHub Actions",
+            System::GitLabCi => "GitLab CI",
+            System::ForgejoActions => "Forgejo Actions",
+        }
+    }
+
+    fn template(&self) -> &'static str {
+        match self {
+            System::GitHubActions | System::ForgejoActions => "actions.tera",
+            System::GitLabCi => "gitlab.tera",
+        }
+    }
+}
+
+/// A job of the pipeline, run once per toolchain.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Job {
+    pub id: String,
+    pub name: String,
+    pub ecosystem: Ecosystem,
+    /// Rust toolchains to run the job with, the MSRV included
+    pub toolchains: Vec<String>,
+    pub commands: Vec<String>,
+}
+
+impl Job {
+    fn new(id: &str, name: &str, ecosystem: Ecosystem, commands: &[&str]) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            ecosystem,
+            toolchains: Vec::new(),
+            commands: commands.iter().map(|command| command.to_string()).collect(),
+        }
+    }
+
+    fn with_toolchains(mut self, toolchains: Vec<String>) -> Self {
+        self.toolchains = toolchains;
+        self
+    }
+}
+
+/// The CI configuration of a project.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pipeline {
+    pub system: System,
+    pub jobs: Vec<Job>,
+}
+
+/// What the README says about the pipeline.
+#[derive(Debug, Serialize)]
+pub struct Summary<'a> {
+    pub title: &'static str,
+    pub file: &'static str,
+    pub jobs: &'a [Job],
+}
+
+impl Pipeline {
+    /// The pipeline of the project in `project_folder`, hosted at
+    /// `repository`. `None` when no ecosystem is detected.
+    pub fn detect(
+        project_folder: &Path,
+        repository: Option<&str>,
+        rust_version: Option<&str>,
+    ) -> Option<Self> {
+        let mut jobs = Vec::new();
+        for ecosystem in Ecosystem::detect(project_folder) {
+            match ecosystem {
+                Ecosystem::Rust => {
+                    let stable = vec!["stable".to_string()];
+                    let mut toolchains = stable.clone();
+                    toolchains.extend(rust_version.map(String::from));
+                    jobs.push(
+                        Job::new(
+                            "fmt",
+                            "Formatting",
+                            ecosystem,
+                            &["cargo fmt --all -- --check"],
+                        )
+                        .with_toolchains(stable.clone()),
+                    );
+                    jobs.push(
+                        Job::new(
+                            "clippy",
+                            "Clippy",
+                            ecosystem,
+                            &["cargo clippy --all-targets -- -D warnings"],
+                        )
+                        .with_toolchains(stable),
+                    );
+                    jobs.push(
+                        Job::new("test", "Tests", ecosystem, &["cargo test"])
+                            .with_toolchains(toolchains),
+                    );
+                }
+                Ecosystem::Node => {
+                    let scripts = Node::new(project_folder)
+                        .info()
+                        .map(|info| info.scripts.clone())
+                        .unwrap_or_default();
+                    if scripts.iter().any(|script| script == "lint") {
+                        jobs.push(Job::new(
+                            "npm-lint",
+                            "npm lint",
+                            ecosystem,
+                            &["npm ci", "npm run lint"],
+                        ));
+                    }
+                    if scripts.iter().any(|script| script == "test") {
+                        jobs.push(Job::new(
+                            "npm-test",
+                            "npm tests",
+                            ecosystem,
+                            &["npm ci", "npm test"],
+                        ));
+                    }
+                }
+                Ecosystem::Python => {
+                    let install = if project_folder.join("requirements.txt").is_file() {
+                        "pip install -r requirements.txt"
+                    } else {
+                        "pip install -e ."
+                    };
+                    jobs.push(Job::new(
+                        "pytest",
+                        "pytest",
+                        ecosystem,
+                        &[install, "pip install pytest", "pytest"],
+                    ));
+                }
+            }
+        }
+        if jobs.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            system: System::for_forge(repository.and_then(Forge::from_url)),
+            jobs,
+        })
+    }
+
+    pub fn summary(&self) -> Summary<'_> {
+        Summary {
+            title: self.system.title(),
+            file: self.system.path(),
+            jobs: &self.jobs,
+        }
+    }
+
+    /// Renders the configuration with the bundled templates.
+    pub fn render(&self) -> Result<String, tera::Error> {
+        let tera = template::load(TEMPLATE_SET, None)?;
+        let mut context = Context::new();
+        context.insert("jobs", &self.jobs);
+        // Forgejo runners fetch actions from their own mirror unless told
+        // where they come from
+        let (runner, actions) = match self.system {
+            System::ForgejoActions => ("docker", "https://github.com/"),
+            _ => ("ubuntu-latest", ""),
+        };
+        context.insert("runner", runner);
+        context.insert("actions", actions);
+
+        let mut rendered = tera.render(self.system.template(), &context)?;
+        if !rendered.ends_with('\n') {
+            rendered.push('\n');
+        }
+        Ok(rendered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detect_rust_pipeline() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            "[package]\nname = \"demo\"\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("package.json"),
+            r#"{"name": "demo", "scripts": {"build": "tsc"}}"#,
+        )
+        .unwrap();
+
+        let pipeline = Pipeline::detect(
+            temp_dir.path(),
+            Some("https://gitlab.com/acme/demo"),
+            Some("1.75"),
+        )
+        .unwrap();
+        assert_eq!(pipeline.system, System::GitLabCi);
+        let ids: Vec<&str> = pipeline.jobs.iter().map(|job| job.id.as_str()).collect();
+        assert_eq!(ids, vec!["fmt", "clippy", "test"]);
+        assert_eq!(pipeline.jobs[2].toolchains, vec!["stable", "1.75"]);
+
+        assert!(Pipeline::detect(&temp_dir.path().join("empty"), None, None).is_none());
+    }
+
+    #[test]
+    fn test_render_every_system() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            "[package]\nname = \"demo\"\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("package.json"),
+            r#"{"name": "demo", "scripts": {"test": "jest"}}"#,
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("requirements.txt"), "requests\n").unwrap();
+
+        for repository in [
+            "https://github.com/acme/demo",
+            "https://gitlab.com/acme/demo",
+            "https://codeberg.org/acme/demo",
+        ] {
+            let pipeline =
+                Pipeline::detect(temp_dir.path(), Some(repository), Some("1.75")).unwrap();
+            let rendered = pipeline.render().unwrap();
+            assert!(rendered.contains("cargo clippy --all-targets -- -D warnings"));
+            assert!(rendered.contains("npm test"));
+            assert!(rendered.contains("pip install -r requirements.txt"));
+            assert!(rendered.contains("1.75"), "{}", repository);
+        }
+
+        let github = Pipeline::detect(temp_dir.path(), None, Some("1.75"))
+            .unwrap()
+            .render()
+            .unwrap();
+        assert!(github.contains("toolchain: [\"stable\", \"1.75\"]"));
+        assert!(github.contains("toolchain: ${{ matrix.toolchain }}"));
+    }
+}
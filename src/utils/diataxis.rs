@@ -0,0 +1,245 @@
+use crate::licenses::License;
+use crate::utils::template;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tera::Context;
+
+/// Bundled templates of the documentation tree, see `template::load`.
+pub const TEMPLATE_SET: &str = "diataxis";
+
+/// The four kinds of documentation described by Diátaxis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quadrant {
+    Tutorials,
+    HowTo,
+    Reference,
+    Explanation,
+}
+
+impl Quadrant {
+    pub const ALL: [Quadrant; 4] = [
+        Quadrant::Tutorials,
+        Quadrant::HowTo,
+        Quadrant::Reference,
+        Quadrant::Explanation,
+    ];
+
+    /// Directory of the quadrant, relative to the root of the tree.
+    pub fn dir(&self) -> &'static str {
+        match self {
+            Quadrant::Tutorials => "tutorials",
+            Quadrant::HowTo => "how-to",
+            Quadrant::Reference => "reference",
+            Quadrant::Explanation => "explanation",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Quadrant::Tutorials => "Tutorials",
+            Quadrant::HowTo => "How-to guides",
+            Quadrant::Reference => "Reference",
+            Quadrant::Explanation => "Explanation",
+        }
+    }
+
+    pub fn purpose(&self) -> &'static str {
+        match self {
+            Quadrant::Tutorials => "Lessons that take you by the hand through your first steps.",
+            Quadrant::HowTo => "Recipes solving a specific problem, step by step.",
+            Quadrant::Reference => "Technical description of the project and how to operate it.",
+            Quadrant::Explanation => "Background and discussion clarifying how and why it works.",
+        }
+    }
+}
+
+/// Project metadata the starter pages are generated from.
+#[derive(Debug, Serialize)]
+pub struct Project {
+    pub project_name: String,
+    pub description: String,
+    pub version: String,
+    pub license: License,
+    pub package_name: Option<String>,
+    pub bins: Vec<String>,
+    pub features: Vec<String>,
+    /// Shell commands installing the project
+    pub installation: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct QuadrantContext {
+    dir: &'static str,
+    title: &'static str,
+    purpose: &'static str,
+}
+
+impl From<Quadrant> for QuadrantContext {
+    fn from(quadrant: Quadrant) -> Self {
+        Self {
+            dir: quadrant.dir(),
+            title: quadrant.title(),
+            purpose: quadrant.purpose(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct PageLink {
+    title: String,
+    file: String,
+}
+
+// Starter pages: quadrant, template and page title
+fn starter_pages(project: &Project) -> Vec<(Quadrant, &'static str, String)> {
+    let mut pages = Vec::new();
+    if !project.installation.is_empty() {
+        pages.push((
+            Quadrant::Tutorials,
+            "tutorials/getting_started.tera",
+            format!("Getting started with {}", project.project_name),
+        ));
+    }
+    pages.push((
+        Quadrant::Reference,
+        "reference/project.tera",
+        format!("{} reference", project.project_name),
+    ));
+    pages
+}
+
+/// Renders the whole tree with the bundled templates.
+/// Returns every page with its path relative to the root of the tree.
+pub fn render(project: &Project) -> Result<Vec<(PathBuf, String)>, tera::Error> {
+    let tera = template::load(TEMPLATE_SET, None)?;
+    let mut context = Context::from_serialize(project)?;
+    let quadrants: Vec<QuadrantContext> = Quadrant::ALL.into_iter().map(Into::into).collect();
+    context.insert("quadrants", &quadrants);
+
+    let mut pages = vec![(
+        PathBuf::from("index.md"),
+        page(tera.render("index.tera", &context)?),
+    )];
+    let starters = starter_pages(project);
+    for quadrant in Quadrant::ALL {
+        let mut links = Vec::new();
+        for (_, template, title) in starters.iter().filter(|(q, _, _)| *q == quadrant) {
+            let file = Path::new(template)
+                .with_extension("md")
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let path = Path::new(quadrant.dir()).join(&file);
+            pages.push((path, page(tera.render(template, &context)?)));
+            links.push(PageLink {
+                title: title.clone(),
+                file,
+            });
+        }
+
+        let mut quadrant_context = context.clone();
+        quadrant_context.insert("quadrant", &QuadrantContext::from(quadrant));
+        quadrant_context.insert("pages", &links);
+        pages.push((
+            Path::new(quadrant.dir()).join("index.md"),
+            page(tera.render("quadrant.tera", &quadrant_context)?),
+        ));
+    }
+    Ok(pages)
+}
+
+// Pages end with exactly one newline, whatever the templates leave behind
+fn page(rendered: String) -> String {
+    format!("{}\n", rendered.trim_end())
+}
+
+/// Quadrants of the tree at `root` holding no page besides their index.
+pub fn empty_quadrants(root: &Path) -> Vec<Quadrant> {
+    Quadrant::ALL
+        .into_iter()
+        .filter(|quadrant| !has_pages(&root.join(quadrant.dir())))
+        .collect()
+}
+
+fn has_pages(dir: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return false;
+    };
+    entries.filter_map(|entry| entry.ok()).any(|entry| {
+        let path = entry.path();
+        if path.is_dir() {
+            has_pages(&path)
+        } else {
+            path.file_name().is_some_and(|name| name != "index.md")
+                && fs::read_to_string(&path).is_ok_and(|content| !content.trim().is_empty())
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::licenses::LicenseCategory;
+    use tempfile::TempDir;
+
+    fn create_test_project() -> Project {
+        Project {
+            project_name: "Demo".to_string(),
+            description: "A demo project".to_string(),
+            version: "0.1.0".to_string(),
+            license: License::new(
+                "MIT",
+                "MIT License",
+                LicenseCategory::Permissive,
+                true,
+                true,
+                false,
+            ),
+            package_name: Some("demo".to_string()),
+            bins: vec!["demo".to_string()],
+            features: vec!["serde".to_string()],
+            installation: vec!["cargo install demo".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_render_tree() {
+        let pages = render(&create_test_project()).unwrap();
+        let page = |path: &str| {
+            pages
+                .iter()
+                .find(|(p, _)| p == Path::new(path))
+                .map(|(_, content)| content.as_str())
+                .unwrap_or_else(|| panic!("{} not rendered", path))
+        };
+
+        assert!(page("index.md").contains("- [How-to guides](how-to/index.md)"));
+        assert!(page("tutorials/index.md")
+            .contains("- [Getting started with Demo](getting_started.md)"));
+        assert!(page("tutorials/getting_started.md").contains("cargo install demo"));
+        assert!(page("reference/project.md").contains("## Cargo features\n\n- `serde`"));
+        assert!(page("how-to/index.md").starts_with("# How-to guides\n"));
+        assert_eq!(pages.len(), 7);
+    }
+
+    #[test]
+    fn test_empty_quadrants() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        for quadrant in Quadrant::ALL {
+            fs::create_dir_all(root.join(quadrant.dir())).unwrap();
+            fs::write(root.join(quadrant.dir()).join("index.md"), "# Index\n").unwrap();
+        }
+        fs::write(root.join("tutorials/first.md"), "# First\n").unwrap();
+        fs::create_dir_all(root.join("how-to/deploy")).unwrap();
+        fs::write(root.join("how-to/deploy/docker.md"), "# Docker\n").unwrap();
+        fs::write(root.join("reference/blank.md"), "\n").unwrap();
+        fs::remove_dir_all(root.join("explanation")).unwrap();
+
+        assert_eq!(
+            empty_quadrants(root),
+            vec![Quadrant::Reference, Quadrant::Explanation]
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use colored::*;
+use similar::TextDiff;
+
+/// Renders a unified diff between two texts with three lines of context, or
+/// `None` when they are identical.
+pub fn unified(old: &str, new: &str, old_name: &str, new_name: &str) -> Option<String> {
+    if old == new {
+        return None;
+    }
+
+    let diff = TextDiff::from_lines(old, new);
+    Some(
+        diff.unified_diff()
+            .context_radius(3)
+            .header(old_name, new_name)
+            .to_string(),
+    )
+}
+
+/// Colors a unified diff the way `git diff` does: headers in bold, hunk
+/// ranges in cyan, additions in green and removals in red.
+pub fn colorize(unified: &str) -> String {
+    unified
+        .lines()
+        .map(|line| {
+            let colored = if line.starts_with("+++") || line.starts_with("---") {
+                line.bold()
+            } else if line.starts_with("@@") {
+                line.cyan()
+            } else if line.starts_with('+') {
+                line.green()
+            } else if line.starts_with('-') {
+                line.red()
+            } else {
+                line.normal()
+            };
+            format!("{}\n", colored)
+        })
+        .collect()
+}
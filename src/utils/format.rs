@@ -0,0 +1,89 @@
+use crate::utils::regions::Markers;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The markup a document is rendered in.
+///
+/// Every format but HTML has its own set of templates; HTML pages are built
+/// from the Markdown render. DocBook templates produce a DocBook 5 `article`,
+/// or a `book` when rendered from `book.tera`.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    #[default]
+    Markdown,
+    Asciidoc,
+    Rst,
+    Html,
+    Docbook,
+}
+
+impl Format {
+    /// Name of the bundled template set rendering this format.
+    pub fn template_set(&self) -> &'static str {
+        match self {
+            Format::Markdown | Format::Html => "markdown",
+            Format::Asciidoc => "asciidoc",
+            Format::Rst => "rst",
+            Format::Docbook => "docbook",
+        }
+    }
+
+    pub fn default_file(&self) -> &'static str {
+        match self {
+            Format::Markdown => "README.md",
+            Format::Asciidoc => "README.adoc",
+            Format::Rst => "README.rst",
+            Format::Html => "README.html",
+            Format::Docbook => "README.xml",
+        }
+    }
+
+    /// How managed region markers are written, as a comment of the format.
+    pub fn markers(&self) -> Markers {
+        match self {
+            Format::Markdown | Format::Html => Markers::HTML_COMMENT,
+            Format::Docbook => Markers {
+                closing: Some("footer"),
+                ..Markers::HTML_COMMENT
+            },
+            Format::Asciidoc => Markers {
+                open: "// ",
+                close: "",
+                padded: false,
+                closing: None,
+            },
+            // a comment must be separated from the following paragraph
+            Format::Rst => Markers {
+                open: ".. ",
+                close: "",
+                padded: true,
+                closing: None,
+            },
+        }
+    }
+
+    /// Whether values are XML-escaped when inserted in the templates.
+    pub fn escapes_xml(&self) -> bool {
+        *self == Format::Docbook
+    }
+
+    pub fn is_markdown(&self) -> bool {
+        *self == Format::Markdown
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Format::Markdown => write!(f, "Markdown"),
+            Format::Asciidoc => write!(f, "AsciiDoc"),
+            Format::Rst => write!(f, "reStructuredText"),
+            Format::Html => write!(f, "HTML"),
+            Format::Docbook => write!(f, "DocBook"),
+        }
+    }
+}
@@ -1,4 +1,6 @@
 mod parser;
+mod remote;
 mod types;
 
+pub use remote::{web_url, Forge};
 pub use types::{Git, GitInfo};
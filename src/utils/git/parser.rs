@@ -1,6 +1,7 @@
 use super::GitInfo;
 use std::fs;
 use std::path::Path;
+use std::process::Command;
 
 pub fn parse_git_info(git_path: &Path) -> GitInfo {
     GitInfo {
@@ -44,21 +45,57 @@ pub fn read_remote_url(git_path: &Path) -> Option<String> {
 
 /// Extracts the tags from the git repository.
 ///
-/// # TODO
-/// - Handle multiple tags (currently returns only the first tag found)
-/// - Consider returning a Vec<String> for all tags and let the user choose the one they want
+/// Both loose tags under `refs/tags` and tags stored in `packed-refs` are
+/// returned, sorted and without duplicates.
 pub fn read_tags(git_path: &Path) -> Vec<String> {
-    let tags = git_path.join("refs/tags");
-    let tags = fs::read_dir(tags).unwrap();
-    let tags = tags
-        .map(|tag| {
-            tag.unwrap()
-                .path()
-                .file_name()
-                .unwrap()
-                .to_string_lossy()
-                .to_string()
+    let mut tags: Vec<String> = fs::read_dir(git_path.join("refs/tags"))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect()
         })
-        .collect();
+        .unwrap_or_default();
+
+    if let Ok(packed_refs) = fs::read_to_string(git_path.join("packed-refs")) {
+        tags.extend(packed_refs.lines().filter_map(|line| {
+            line.split_whitespace()
+                .nth(1)?
+                .strip_prefix("refs/tags/")
+                .map(String::from)
+        }));
+    }
+
+    tags.sort();
+    tags.dedup();
     tags
 }
+
+/// Returns the full messages of the commits reachable from `HEAD` but not
+/// from `since` (or every commit when `since` is `None`), newest first.
+///
+/// Walking the object database by hand would mean inflating packfiles, so
+/// this delegates to the `git` binary and returns an empty list when it is
+/// not available.
+pub fn read_commit_messages(git_path: &Path, since: Option<&str>) -> Vec<String> {
+    let range = match since {
+        Some(tag) => format!("{}..HEAD", tag),
+        None => "HEAD".to_string(),
+    };
+
+    let output = match Command::new("git")
+        .arg("--git-dir")
+        .arg(git_path)
+        .args(["log", "--format=%B%x1e", &range])
+        .output()
+    {
+        Ok(output) if output.status.success() => output,
+        _ => return Vec::new(),
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .split('\x1e')
+        .map(|message| message.trim().to_string())
+        .filter(|message| !message.is_empty())
+        .collect()
+}
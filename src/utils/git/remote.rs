@@ -0,0 +1,92 @@
+/// Turns a git remote URL into the browsable `https://` address of the
+/// repository, so that `git@host:owner/repo.git`, `ssh://git@host/owner/repo`
+/// and `https://host/owner/repo.git` all compare equal.
+pub fn web_url(remote_url: &str) -> Option<String> {
+    let url = remote_url.trim();
+    let url = url.strip_prefix("git+").unwrap_or(url);
+
+    let (host, path) = if let Some(rest) = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .or_else(|| url.strip_prefix("ssh://"))
+        .or_else(|| url.strip_prefix("git://"))
+    {
+        let rest = rest.rsplit_once('@').map_or(rest, |(_, host)| host);
+        rest.split_once('/')?
+    } else {
+        // scp-like syntax: user@host:owner/repo.git
+        let rest = url.rsplit_once('@').map_or(url, |(_, host)| host);
+        rest.split_once(':')?
+    };
+
+    // drop any explicit port, which only makes sense for the original protocol
+    let host = host.split(':').next()?;
+    let path = path.trim_end_matches('/').trim_end_matches(".git");
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+
+    Some(format!("https://{}/{}", host.to_lowercase(), path))
+}
+
+/// The service hosting a repository, which decides where its CI runs and
+/// what its pages are called.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Forge {
+    GitHub,
+    GitLab,
+    Codeberg,
+    Bitbucket,
+}
+
+impl Forge {
+    /// The forge of a repository from its web address, as [`web_url`]
+    /// returns it. Self-hosted GitLab instances are recognized by name.
+    pub fn from_url(web_url: &str) -> Option<Self> {
+        let host = web_url
+            .strip_prefix("https://")?
+            .split('/')
+            .next()?
+            .to_lowercase();
+        match host.as_str() {
+            "github.com" => Some(Self::GitHub),
+            "codeberg.org" => Some(Self::Codeberg),
+            "bitbucket.org" => Some(Self::Bitbucket),
+            _ if host == "gitlab.com" || host.starts_with("gitlab.") => Some(Self::GitLab),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_web_url() {
+        let expected = Some("https://github.com/Solexma/wtfm".to_string());
+        assert_eq!(web_url("git@github.com:Solexma/wtfm.git"), expected);
+        assert_eq!(web_url("https://github.com/Solexma/wtfm.git"), expected);
+        assert_eq!(web_url("https://github.com/Solexma/wtfm/"), expected);
+        assert_eq!(web_url("ssh://git@github.com:22/Solexma/wtfm"), expected);
+        assert_eq!(web_url("git+https://github.com/Solexma/wtfm.git"), expected);
+        assert_eq!(web_url("not a url"), None);
+    }
+
+    #[test]
+    fn test_forge_from_url() {
+        assert_eq!(
+            Forge::from_url("https://github.com/Solexma/wtfm"),
+            Some(Forge::GitHub)
+        );
+        assert_eq!(
+            Forge::from_url("https://gitlab.example.com/group/project"),
+            Some(Forge::GitLab)
+        );
+        assert_eq!(
+            Forge::from_url("https://codeberg.org/Solexma/wtfm"),
+            Some(Forge::Codeberg)
+        );
+        assert_eq!(Forge::from_url("https://example.com/Solexma/wtfm"), None);
+    }
+}
@@ -1,8 +1,9 @@
 use super::parser;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct GitInfo {
     pub is_git_repo: bool,
     pub current_branch: Option<String>,
@@ -33,4 +34,13 @@ impl Git {
         let git_path = self.path.join(".git");
         Some(parser::parse_git_info(&git_path))
     }
+
+    /// Commit messages since `tag`, or the whole history when `tag` is `None`.
+    pub fn commits_since(&self, tag: Option<&str>) -> Vec<String> {
+        if !self.is_repo() {
+            return Vec::new();
+        }
+
+        parser::read_commit_messages(&self.path.join(".git"), tag)
+    }
 }
@@ -0,0 +1,72 @@
+use pulldown_cmark::{html, Options, Parser};
+
+const STYLESHEET: &str = r#"
+body { max-width: 46rem; margin: 2rem auto; padding: 0 1rem; font: 16px/1.6 system-ui, sans-serif; color: #1f2328; }
+h1, h2, h3 { line-height: 1.25; }
+h1, h2 { border-bottom: 1px solid #d1d9e0; padding-bottom: .3em; }
+a { color: #0969da; }
+code, pre { font-family: ui-monospace, monospace; background: #f6f8fa; border-radius: 6px; }
+code { padding: .2em .4em; }
+pre { padding: 1rem; overflow: auto; }
+pre code { padding: 0; }
+img { max-width: 100%; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #d1d9e0; padding: .4em .8em; }
+"#;
+
+/// The CommonMark extensions enabled when rendering Markdown: tables,
+/// strikethrough and task lists.
+pub fn markdown_options() -> Options {
+    Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS
+}
+
+/// Converts CommonMark to HTML.
+pub fn markdown_to_html(markdown: &str) -> String {
+    let mut body = String::new();
+    html::push_html(&mut body, Parser::new_ext(markdown, markdown_options()));
+    body
+}
+
+/// Wraps an HTML fragment in a self-contained page with a minimal stylesheet.
+pub fn standalone_page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape(title),
+        STYLESHEET,
+        body
+    )
+}
+
+/// Escapes the characters that are significant in HTML and XML text and
+/// attributes.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standalone_page() {
+        let body = markdown_to_html("# Title\n\nSome *text*\n");
+        assert_eq!(body, "<h1>Title</h1>\n<p>Some <em>text</em></p>\n");
+
+        let page = standalone_page("A <b> & \"c\"", &body);
+        assert!(page.starts_with("<!DOCTYPE html>"));
+        assert!(page.contains("<title>A &lt;b&gt; &amp; &quot;c&quot;</title>"));
+        assert!(page.contains("<style>"));
+        assert!(page.contains(&body));
+    }
+}
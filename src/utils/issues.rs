@@ -0,0 +1,155 @@
+use crate::utils::ci::Ecosystem;
+use crate::utils::git::Forge;
+use crate::utils::template;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tera::Context;
+
+/// Bundled issue and pull request templates, see `template::load`.
+pub const TEMPLATE_SET: &str = "issues";
+
+/// What the issue and pull request templates are filled with.
+#[derive(Debug, Serialize)]
+pub struct Project {
+    pub project_name: String,
+    pub version: String,
+    /// Commands whose output tells which versions a reporter runs
+    pub version_commands: Vec<String>,
+    /// Commands a pull request must pass, as the CI runs them
+    pub checks: Vec<String>,
+}
+
+impl Project {
+    /// Version commands of the project binaries first, then of the tooling
+    /// of each ecosystem.
+    pub fn version_commands(bins: &[String], ecosystems: &[Ecosystem]) -> Vec<String> {
+        let mut commands: Vec<String> = bins
+            .iter()
+            .map(|bin| format!("{} --version", bin))
+            .collect();
+        for ecosystem in ecosystems {
+            let tooling: &[&str] = match ecosystem {
+                Ecosystem::Rust => &["rustc -V", "cargo --version"],
+                Ecosystem::Node => &["node --version", "npm --version"],
+                Ecosystem::Python => &["python --version", "pip --version"],
+            };
+            commands.extend(tooling.iter().map(|command| command.to_string()));
+        }
+        commands
+    }
+}
+
+// Templates of each forge and where they go, relative to the project directory
+fn files(forge: Forge) -> Result<&'static [(&'static str, &'static str)], String> {
+    match forge {
+        Forge::GitHub => Ok(&[
+            (
+                "github/bug_report.yml.tera",
+                ".github/ISSUE_TEMPLATE/bug_report.yml",
+            ),
+            (
+                "github/feature_request.yml.tera",
+                ".github/ISSUE_TEMPLATE/feature_request.yml",
+            ),
+            (
+                "github/pull_request_template.md.tera",
+                ".github/pull_request_template.md",
+            ),
+        ]),
+        // Forgejo reads the same issue forms as GitHub
+        Forge::Codeberg => Ok(&[
+            (
+                "github/bug_report.yml.tera",
+                ".forgejo/issue_template/bug_report.yml",
+            ),
+            (
+                "github/feature_request.yml.tera",
+                ".forgejo/issue_template/feature_request.yml",
+            ),
+            (
+                "github/pull_request_template.md.tera",
+                ".forgejo/pull_request_template.md",
+            ),
+        ]),
+        Forge::GitLab => Ok(&[
+            ("gitlab/bug.md.tera", ".gitlab/issue_templates/Bug.md"),
+            (
+                "gitlab/feature.md.tera",
+                ".gitlab/issue_templates/Feature.md",
+            ),
+            (
+                "gitlab/merge_request.md.tera",
+                ".gitlab/merge_request_templates/Default.md",
+            ),
+        ]),
+        Forge::Bitbucket => Err("Bitbucket doesn't support issue templates".to_string()),
+    }
+}
+
+/// Renders the templates of `forge` with the bundled templates, replaced by
+/// those with the same name under `overrides`. Returns every file with its
+/// path relative to the project directory.
+pub fn render(
+    project: &Project,
+    forge: Forge,
+    overrides: Option<&Path>,
+) -> Result<Vec<(PathBuf, String)>, Box<dyn std::error::Error>> {
+    let files = files(forge)?;
+    let tera = template::load(TEMPLATE_SET, overrides)?;
+    let context = Context::from_serialize(project)?;
+
+    let mut rendered = Vec::new();
+    for (template, destination) in files {
+        let mut content = tera.render(template, &context)?;
+        if !content.ends_with('\n') {
+            content.push('\n');
+        }
+        rendered.push((PathBuf::from(destination), content));
+    }
+    Ok(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project() -> Project {
+        Project {
+            project_name: "Demo: the tool".to_string(),
+            version: "1.2.0".to_string(),
+            version_commands: Project::version_commands(&["demo".to_string()], &[Ecosystem::Rust]),
+            checks: vec!["cargo test".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_render_github_forms() {
+        let files = render(&project(), Forge::GitHub, None).unwrap();
+        assert_eq!(files.len(), 3);
+
+        let (path, bug) = &files[0];
+        assert_eq!(path, Path::new(".github/ISSUE_TEMPLATE/bug_report.yml"));
+        // a name with a colon still gives valid YAML
+        assert!(bug.contains("\"Report a bug in Demo: the tool\""));
+        assert!(bug.contains("demo --version"));
+        assert!(bug.contains("rustc -V"));
+        assert!(bug.contains("labels: [\"bug\"]"));
+        assert!(files[2].1.contains("`cargo test`"));
+    }
+
+    #[test]
+    fn test_render_gitlab_with_overrides() {
+        let overrides = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(overrides.path().join("gitlab")).unwrap();
+        std::fs::write(
+            overrides.path().join("gitlab/feature.md.tera"),
+            "Ideas for {{ project_name }}",
+        )
+        .unwrap();
+
+        let files = render(&project(), Forge::GitLab, Some(overrides.path())).unwrap();
+        assert!(files[0].1.contains("/label ~bug"));
+        assert_eq!(files[1].1, "Ideas for Demo: the tool\n");
+        assert!(render(&project(), Forge::Bitbucket, None).is_err());
+    }
+}
@@ -0,0 +1,202 @@
+use crate::config::wtfm::CliOption;
+
+/// The content of a section 1 manual page.
+#[derive(Debug)]
+pub struct ManPage {
+    pub name: String,
+    pub version: String,
+    pub summary: String,
+    pub description: String,
+    /// Usage line without the leading command name, e.g. `[OPTIONS] <FILE>`
+    pub usage: Option<String>,
+    pub options: Vec<CliOption>,
+    pub authors: Vec<String>,
+    pub license: String,
+}
+
+impl ManPage {
+    /// Renders the page as roff, ready for `man -l`.
+    pub fn to_roff(&self) -> String {
+        let mut roff = format!(
+            ".TH {} 1 \"\" \"{} {}\" \"User Commands\"\n",
+            self.name.to_uppercase(),
+            escape(&self.name),
+            escape(&self.version)
+        );
+
+        roff.push_str(".SH NAME\n");
+        roff.push_str(&format!(
+            "{} \\- {}\n",
+            escape(&self.name),
+            escape(&self.summary)
+        ));
+
+        roff.push_str(".SH SYNOPSIS\n");
+        roff.push_str(&format!(".B {}\n", escape(&self.name)));
+        match &self.usage {
+            Some(usage) => roff.push_str(&format!("{}\n", escape(usage))),
+            None if !self.options.is_empty() => roff.push_str("[\\fIOPTIONS\\fR]\n"),
+            None => {}
+        }
+
+        roff.push_str(".SH DESCRIPTION\n");
+        roff.push_str(&paragraphs(&self.description));
+
+        if !self.options.is_empty() {
+            roff.push_str(".SH OPTIONS\n");
+            for option in &self.options {
+                roff.push_str(&format!(
+                    ".TP\n\\fB{}\\fR\n{}\n",
+                    escape(&option.flags),
+                    escape(&option.description)
+                ));
+            }
+        }
+
+        roff.push_str(".SH \"EXIT STATUS\"\n");
+        roff.push_str(".TP\n.B 0\nSuccessful program execution.\n");
+        roff.push_str(".TP\n.B 1\nAn error occurred.\n");
+
+        if !self.authors.is_empty() {
+            roff.push_str(".SH AUTHORS\n");
+            let authors: Vec<String> = self.authors.iter().map(|a| escape(a)).collect();
+            roff.push_str(&format!("{}\n", authors.join("\n.br\n")));
+        }
+
+        roff.push_str(".SH LICENSE\n");
+        roff.push_str(&format!("Licensed under {}.\n", escape(&self.license)));
+        roff
+    }
+}
+
+fn paragraphs(text: &str) -> String {
+    text.split("\n\n")
+        .map(str::trim)
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(escape)
+        .collect::<Vec<_>>()
+        .join("\n.PP\n")
+        + "\n"
+}
+
+/// Escapes text for roff: backslashes and hyphens are written as escapes and
+/// lines starting with a control character are protected with `\&`.
+pub fn escape(text: &str) -> String {
+    text.replace('\\', "\\e")
+        .replace('-', "\\-")
+        .lines()
+        .map(|line| {
+            if line.starts_with('.') || line.starts_with('\'') {
+                format!("\\&{}", line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Extracts the usage line and the options from the `--help` output of a
+/// clap-style program. Option descriptions spanning several lines are joined.
+pub fn parse_help(help: &str) -> (Option<String>, Vec<CliOption>) {
+    let mut usage = None;
+    let mut options: Vec<CliOption> = Vec::new();
+    let mut in_options = false;
+
+    for line in help.lines() {
+        let trimmed = line.trim();
+        if let Some(rest) = trimmed.strip_prefix("Usage:") {
+            // drop the program name, the man page already prints it
+            usage = rest
+                .trim()
+                .split_once(char::is_whitespace)
+                .map(|(_, args)| args.trim().to_string())
+                .filter(|args| !args.is_empty());
+            continue;
+        }
+        if !line.starts_with(' ') && trimmed.ends_with(':') {
+            in_options = trimmed.eq_ignore_ascii_case("options:");
+            continue;
+        }
+        if !in_options || trimmed.is_empty() {
+            continue;
+        }
+
+        if trimmed.starts_with('-') {
+            let (flags, description) = match trimmed.split_once("  ") {
+                Some((flags, description)) => (flags.trim(), description.trim()),
+                None => (trimmed, ""),
+            };
+            options.push(CliOption {
+                flags: flags.to_string(),
+                description: description.to_string(),
+            });
+        } else if let Some(option) = options.last_mut() {
+            if !option.description.is_empty() {
+                option.description.push(' ');
+            }
+            option.description.push_str(trimmed);
+        }
+    }
+
+    (usage, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HELP: &str = "This CLI tool will guide you in writing that (F) Manual.
+
+Usage: wtfm [OPTIONS] [COMMAND]
+
+Commands:
+  analyze   Analyze the project
+  help      Print this message
+
+Options:
+  -d, --debug    Enable debug output
+  -o, --output <DIR>
+          Output directory
+          for the page
+  -h, --help     Print help
+";
+
+    #[test]
+    fn test_parse_help() {
+        let (usage, options) = parse_help(HELP);
+        assert_eq!(usage.as_deref(), Some("[OPTIONS] [COMMAND]"));
+        assert_eq!(options.len(), 3);
+        assert_eq!(options[0].flags, "-d, --debug");
+        assert_eq!(options[0].description, "Enable debug output");
+        assert_eq!(options[1].flags, "-o, --output <DIR>");
+        assert_eq!(options[1].description, "Output directory for the page");
+    }
+
+    #[test]
+    fn test_to_roff() {
+        let page = ManPage {
+            name: "wtfm".to_string(),
+            version: "0.1.1".to_string(),
+            summary: "write the manual".to_string(),
+            description: "First paragraph.\n\n.Second paragraph.".to_string(),
+            usage: None,
+            options: vec![CliOption {
+                flags: "-d, --debug".to_string(),
+                description: "Enable debug output".to_string(),
+            }],
+            authors: vec!["Jane Doe <jane@example.com>".to_string()],
+            license: "MIT License".to_string(),
+        };
+        let roff = page.to_roff();
+
+        assert!(roff.starts_with(".TH WTFM 1 \"\" \"wtfm 0.1.1\" \"User Commands\"\n"));
+        assert!(roff.contains(".SH NAME\nwtfm \\- write the manual\n"));
+        assert!(roff.contains(".B wtfm\n[\\fIOPTIONS\\fR]\n"));
+        assert!(roff.contains("First paragraph.\n.PP\n\\&.Second paragraph.\n"));
+        assert!(roff.contains(".TP\n\\fB\\-d, \\-\\-debug\\fR\nEnable debug output\n"));
+        assert!(roff.contains(".SH \"EXIT STATUS\"\n"));
+        assert!(roff.contains(".SH AUTHORS\nJane Doe <jane@example.com>\n"));
+        assert!(roff.ends_with(".SH LICENSE\nLicensed under MIT License.\n"));
+    }
+}
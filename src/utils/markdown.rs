@@ -0,0 +1,121 @@
+/// A Markdown ATX heading (`## Title`) and the line it sits on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heading {
+    pub level: usize,
+    pub title: String,
+    pub line: usize,
+}
+
+/// Lists the headings of a Markdown document, skipping fenced code blocks.
+pub fn headings(markdown: &str) -> Vec<Heading> {
+    let mut in_code_block = false;
+    markdown
+        .lines()
+        .enumerate()
+        .filter_map(|(line, text)| {
+            if text.trim_start().starts_with("```") {
+                in_code_block = !in_code_block;
+                return None;
+            }
+            if in_code_block {
+                return None;
+            }
+            parse_heading(text).map(|(level, title)| Heading { level, title, line })
+        })
+        .collect()
+}
+
+fn parse_heading(line: &str) -> Option<(usize, String)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let title = line[level..]
+        .strip_prefix(' ')?
+        .trim()
+        .trim_end_matches('#');
+    Some((level, title.trim().to_string()))
+}
+
+/// Returns the body of the first section titled `title` (case-insensitive),
+/// up to the next heading of the same or a higher level.
+pub fn section(markdown: &str, title: &str) -> Option<String> {
+    let all = headings(markdown);
+    let index = all
+        .iter()
+        .position(|h| h.title.eq_ignore_ascii_case(title))?;
+    let start = all[index].line + 1;
+    let end = all[index + 1..]
+        .iter()
+        .find(|h| h.level <= all[index].level)
+        .map_or(usize::MAX, |h| h.line);
+
+    Some(
+        markdown
+            .lines()
+            .enumerate()
+            .filter(|(line, _)| *line >= start && *line < end)
+            .map(|(_, text)| text)
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+/// Titles of the headings followed by nothing but blank lines before the
+/// next heading of the same or a higher level (or the end of the document).
+pub fn empty_sections(markdown: &str) -> Vec<String> {
+    let lines: Vec<&str> = markdown.lines().collect();
+    let all = headings(markdown);
+
+    all.iter()
+        .enumerate()
+        .filter(|(index, heading)| {
+            let next = all.get(index + 1);
+            let end = next.map_or(lines.len(), |h| h.line);
+            let has_text = lines[heading.line + 1..end]
+                .iter()
+                .any(|line| !line.trim().is_empty());
+            let has_subsection = next.is_some_and(|h| h.level > heading.level);
+            !has_text && !has_subsection
+        })
+        .map(|(_, heading)| heading.title.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const README: &str = "# Project\n\nIntro\n\n## Getting Started\n\n### Installation\n\n- cargo install\n\n## Usage\n\n```sh\n# not a heading\n```\n\n## Empty\n\n## License\n\nMIT\n\n## Trailing\n";
+
+    #[test]
+    fn test_headings_skip_code_blocks() {
+        let titles: Vec<String> = headings(README).into_iter().map(|h| h.title).collect();
+        assert_eq!(
+            titles,
+            vec![
+                "Project",
+                "Getting Started",
+                "Installation",
+                "Usage",
+                "Empty",
+                "License",
+                "Trailing"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_section() {
+        assert_eq!(section(README, "license").unwrap().trim(), "MIT");
+        assert!(section(README, "Getting Started")
+            .unwrap()
+            .contains("cargo install"));
+        assert_eq!(section(README, "Missing"), None);
+    }
+
+    #[test]
+    fn test_empty_sections() {
+        assert_eq!(empty_sections(README), vec!["Empty", "Trailing"]);
+    }
+}
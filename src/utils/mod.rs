@@ -1,4 +1,18 @@
+pub mod badges;
 pub mod cargo;
+pub mod ci;
+pub mod diataxis;
+pub mod diff;
+pub mod format;
 pub mod git;
+pub mod html;
+pub mod issues;
 pub mod logger;
+pub mod man;
+pub mod markdown;
+pub mod node;
+pub mod regions;
+pub mod site;
 pub mod template;
+pub mod version;
+pub mod watch;
@@ -0,0 +1,36 @@
+use super::NodeInfo;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+pub(crate) fn parse_node_info(package_json_path: &Path) -> Option<NodeInfo> {
+    let content = fs::read_to_string(package_json_path).ok()?;
+    let package: Value = serde_json::from_str(&content).ok()?;
+
+    let read_string = |key: &str| package.get(key)?.as_str().map(String::from);
+
+    // `repository` may be either a shorthand string or an object with a `url`
+    let repository = match package.get("repository") {
+        Some(Value::String(url)) => Some(url.clone()),
+        Some(Value::Object(repository)) => repository
+            .get("url")
+            .and_then(Value::as_str)
+            .map(String::from),
+        _ => None,
+    };
+
+    let scripts = package
+        .get("scripts")
+        .and_then(Value::as_object)
+        .map(|scripts| scripts.keys().cloned().collect())
+        .unwrap_or_default();
+
+    Some(NodeInfo {
+        package_name: read_string("name"),
+        version: read_string("version"),
+        description: read_string("description"),
+        repository,
+        license: read_string("license"),
+        scripts,
+    })
+}
@@ -0,0 +1,44 @@
+use super::parser;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NodeInfo {
+    pub package_name: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub repository: Option<String>,
+    pub license: Option<String>,
+    pub scripts: Vec<String>,
+}
+
+pub struct Node {
+    path: std::path::PathBuf,
+    info: Option<NodeInfo>,
+}
+
+impl Node {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let package_json_path = path.join("package.json");
+        let info = if package_json_path.exists() {
+            parser::parse_node_info(&package_json_path)
+        } else {
+            None
+        };
+
+        Self { path, info }
+    }
+
+    pub fn info(&self) -> Option<&NodeInfo> {
+        self.info.as_ref()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn package_json_path(&self) -> std::path::PathBuf {
+        self.path.join("package.json")
+    }
+}
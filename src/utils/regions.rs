@@ -0,0 +1,431 @@
+use std::collections::HashMap;
+use std::fmt;
+use tera::Value;
+
+/// How region markers are spelled: a line comment of the document format
+/// around `wtfm:start:NAME` or `wtfm:end:NAME`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Markers {
+    pub open: &'static str,
+    pub close: &'static str,
+    /// Whether markers need a blank line between them and the content
+    pub padded: bool,
+    /// Region closing the root element of the document, like `</article>`
+    /// in DocBook, that nothing may follow
+    pub closing: Option<&'static str>,
+}
+
+impl Markers {
+    pub const HTML_COMMENT: Markers = Markers {
+        open: "<!-- ",
+        close: " -->",
+        padded: false,
+        closing: None,
+    };
+
+    fn start(&self, name: &str) -> String {
+        format!("{}wtfm:start:{}{}", self.open, name, self.close)
+    }
+
+    fn end(&self, name: &str) -> String {
+        format!("{}wtfm:end:{}{}", self.open, name, self.close)
+    }
+
+    fn start_name<'a>(&self, line: &'a str) -> Option<&'a str> {
+        line.trim()
+            .strip_prefix(self.open.trim_end())?
+            .trim_start()
+            .strip_prefix("wtfm:start:")?
+            .strip_suffix(self.close.trim_start())
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+    }
+
+    fn wrap(&self, name: &str, content: &str) -> String {
+        let padding = if self.padded { "\n" } else { "" };
+        format!(
+            "{}\n{}{}{}\n{}",
+            self.start(name),
+            padding,
+            content,
+            padding,
+            self.end(name)
+        )
+    }
+}
+
+/// A piece of a document: either prose written by hand or a region owned by
+/// wtfm and delimited by `wtfm:start:NAME`/`wtfm:end:NAME` comments, such as
+/// `<!-- wtfm:start:license -->` in Markdown.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    Custom(String),
+    Managed { name: String, content: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChangeKind {
+    Added,
+    Updated,
+    Removed,
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeKind::Added => write!(f, "added"),
+            ChangeKind::Updated => write!(f, "updated"),
+            ChangeKind::Removed => write!(f, "removed"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectionChange {
+    pub name: String,
+    pub kind: ChangeKind,
+}
+
+/// The result of merging a fresh render into an existing document.
+#[derive(Debug)]
+pub struct Merge {
+    pub content: String,
+    pub changes: Vec<SectionChange>,
+}
+
+/// Tera filter wrapping its input in region markers, used as
+/// `{% filter managed(name="license") %}...{% endfilter %}`.
+/// Sections rendering to nothing produce no region at all.
+pub fn managed_filter(
+    markers: Markers,
+) -> impl Fn(&Value, &HashMap<String, Value>) -> tera::Result<Value> {
+    move |value, args| {
+        let name = args
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| tera::Error::msg("The `managed` filter requires a `name` argument"))?;
+        let content = value.as_str().unwrap_or_default().trim();
+
+        if content.is_empty() {
+            return Ok(Value::String(String::new()));
+        }
+        Ok(Value::String(format!("{}\n", markers.wrap(name, content))))
+    }
+}
+
+/// Splits a document into custom and managed segments. A start marker
+/// without its end marker is kept as custom text so nothing is ever lost.
+pub fn parse(text: &str, markers: Markers) -> Vec<Segment> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut segments = Vec::new();
+    let mut custom: Vec<&str> = Vec::new();
+
+    let mut i = 0;
+    while i < lines.len() {
+        let end = markers.start_name(lines[i]).and_then(|name| {
+            let close = markers.end(name);
+            lines[i + 1..]
+                .iter()
+                .position(|line| line.trim() == close)
+                .map(|offset| (name, i + 1 + offset))
+        });
+
+        match end {
+            Some((name, end)) => {
+                push_custom(&mut segments, &mut custom);
+                segments.push(Segment::Managed {
+                    name: name.to_string(),
+                    content: lines[i + 1..end].join("\n").trim().to_string(),
+                });
+                i = end + 1;
+            }
+            None => {
+                custom.push(lines[i]);
+                i += 1;
+            }
+        }
+    }
+    push_custom(&mut segments, &mut custom);
+    segments
+}
+
+fn push_custom(segments: &mut Vec<Segment>, lines: &mut Vec<&str>) {
+    let text = lines.join("\n");
+    let text = text.trim_matches('\n').trim_end();
+    if !text.trim().is_empty() {
+        segments.push(Segment::Custom(text.to_string()));
+    }
+    lines.clear();
+}
+
+/// Serializes segments back into a document, one blank line apart.
+pub fn render(segments: &[Segment], markers: Markers) -> String {
+    let blocks: Vec<String> = segments
+        .iter()
+        .map(|segment| match segment {
+            Segment::Custom(text) => text.clone(),
+            Segment::Managed { name, content } => markers.wrap(name, content),
+        })
+        .collect();
+
+    if blocks.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n", blocks.join("\n\n"))
+    }
+}
+
+/// Replaces the managed regions of `existing` with the ones in `rendered`.
+///
+/// Custom segments stay where they are and regions keep the order the user
+/// gave them. Regions that are new are placed right after the region that
+/// precedes them in the fresh render, and regions that no longer render are
+/// dropped. A document without any marker is thus imported as a single
+/// custom block following the generated regions, or preceding the region
+/// closing the root element when the format has one.
+pub fn merge(existing: &str, rendered: &str, markers: Markers) -> Merge {
+    let fresh: Vec<(String, String)> = parse(rendered, markers)
+        .into_iter()
+        .filter_map(|segment| match segment {
+            Segment::Managed { name, content } => Some((name, content)),
+            Segment::Custom(_) => None,
+        })
+        .collect();
+    let fresh_content = |name: &str| {
+        fresh
+            .iter()
+            .find(|(fresh_name, _)| fresh_name == name)
+            .map(|(_, content)| content.clone())
+    };
+
+    let mut segments = Vec::new();
+    let mut changes = Vec::new();
+    for segment in parse(existing, markers) {
+        match segment {
+            Segment::Custom(text) => segments.push(Segment::Custom(text)),
+            Segment::Managed { name, content } => match fresh_content(&name) {
+                Some(updated) => {
+                    if updated != content {
+                        changes.push(SectionChange {
+                            name: name.clone(),
+                            kind: ChangeKind::Updated,
+                        });
+                    }
+                    segments.push(Segment::Managed {
+                        name,
+                        content: updated,
+                    });
+                }
+                None => changes.push(SectionChange {
+                    name,
+                    kind: ChangeKind::Removed,
+                }),
+            },
+        }
+    }
+
+    let position_of = |segments: &[Segment], wanted: &str| {
+        segments
+            .iter()
+            .rposition(|segment| matches!(segment, Segment::Managed { name, .. } if name == wanted))
+    };
+    for (index, (name, content)) in fresh.iter().enumerate() {
+        if position_of(&segments, name).is_some() {
+            continue;
+        }
+
+        let after_previous = fresh[..index]
+            .iter()
+            .rev()
+            .find_map(|(previous, _)| position_of(&segments, previous))
+            .map(|position| position + 1);
+        let before_first_region = || {
+            segments
+                .iter()
+                .position(|segment| matches!(segment, Segment::Managed { .. }))
+        };
+        let at = after_previous.or_else(before_first_region).unwrap_or(0);
+
+        segments.insert(
+            at,
+            Segment::Managed {
+                name: name.clone(),
+                content: content.clone(),
+            },
+        );
+        changes.push(SectionChange {
+            name: name.clone(),
+            kind: ChangeKind::Added,
+        });
+    }
+
+    // Custom text past the end of the root element would leave the document
+    // invalid, it goes inside
+    if let Some(closing) = markers.closing {
+        if let Some(position) = position_of(&segments, closing) {
+            let mut trailing: Vec<Segment> = segments.drain(position + 1..).collect();
+            let closing = segments.pop().expect("the closing region is last");
+            segments.append(&mut trailing);
+            segments.push(closing);
+        }
+    }
+
+    Merge {
+        content: render(&segments, markers),
+        changes,
+    }
+}
+
+/// Drops the region markers, keeping the content of every segment.
+pub fn strip(text: &str, markers: Markers) -> String {
+    let segments: Vec<Segment> = parse(text, markers)
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Managed { content, .. } => Segment::Custom(content),
+            custom => custom,
+        })
+        .collect();
+    render(&segments, markers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MARKERS: Markers = Markers::HTML_COMMENT;
+
+    fn region(name: &str, content: &str) -> String {
+        format!("{}\n", MARKERS.wrap(name, content))
+    }
+
+    #[test]
+    fn test_parse_keeps_unterminated_marker_as_custom() {
+        let text = format!(
+            "Intro\n\n{}\n<!-- wtfm:start:broken -->\ntail",
+            region("license", "MIT")
+        );
+        assert_eq!(
+            parse(&text, MARKERS),
+            vec![
+                Segment::Custom("Intro".to_string()),
+                Segment::Managed {
+                    name: "license".to_string(),
+                    content: "MIT".to_string()
+                },
+                Segment::Custom("<!-- wtfm:start:broken -->\ntail".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_preserves_custom_prose() {
+        let existing = format!(
+            "{}\nHand-written notes\n\n{}",
+            region("header", "# Old"),
+            region("license", "MIT")
+        );
+        let rendered = format!("{}{}", region("header", "# New"), region("license", "MIT"));
+
+        let merged = merge(&existing, &rendered, MARKERS);
+        assert_eq!(
+            merged.content,
+            format!(
+                "{}\nHand-written notes\n\n{}",
+                region("header", "# New"),
+                region("license", "MIT")
+            )
+        );
+        assert_eq!(
+            merged.changes,
+            vec![SectionChange {
+                name: "header".to_string(),
+                kind: ChangeKind::Updated
+            }]
+        );
+    }
+
+    #[test]
+    fn test_merge_places_new_and_drops_stale_regions() {
+        let existing = format!(
+            "{}\n{}\nFooter",
+            region("header", "# Title"),
+            region("ci", "CI")
+        );
+        let rendered = format!(
+            "{}{}",
+            region("header", "# Title"),
+            region("license", "MIT")
+        );
+
+        let merged = merge(&existing, &rendered, MARKERS);
+        assert_eq!(
+            merged.content,
+            format!(
+                "{}\n{}\nFooter\n",
+                region("header", "# Title"),
+                region("license", "MIT")
+            )
+        );
+        let kinds: Vec<ChangeKind> = merged.changes.iter().map(|c| c.kind).collect();
+        assert_eq!(kinds, vec![ChangeKind::Removed, ChangeKind::Added]);
+    }
+
+    #[test]
+    fn test_merge_imports_unmanaged_readme() {
+        let merged = merge(
+            "# My README\n\nSome text\n",
+            &region("header", "# Title"),
+            MARKERS,
+        );
+        assert_eq!(
+            merged.content,
+            format!(
+                "{}\n# My README\n\nSome text\n",
+                region("header", "# Title")
+            )
+        );
+    }
+
+    #[test]
+    fn test_merge_keeps_custom_text_inside_the_root() {
+        let docbook = Markers {
+            closing: Some("footer"),
+            ..MARKERS
+        };
+        let rendered = format!(
+            "{}\n{}\n",
+            region("header", "<article>"),
+            region("footer", "</article>")
+        );
+        let merged = merge("<para>Mine</para>\n", &rendered, docbook);
+        assert_eq!(
+            merged.content,
+            format!(
+                "{}\n<para>Mine</para>\n\n{}",
+                region("header", "<article>"),
+                region("footer", "</article>")
+            )
+        );
+        assert_eq!(
+            merge(&merged.content, &rendered, docbook).content,
+            merged.content
+        );
+    }
+
+    #[test]
+    fn test_padded_markers_round_trip() {
+        let rst = Markers {
+            open: ".. ",
+            close: "",
+            padded: true,
+            closing: None,
+        };
+        let text = format!("Intro\n\n{}\n", rst.wrap("license", "License\n-------"));
+        assert_eq!(
+            text,
+            "Intro\n\n.. wtfm:start:license\n\nLicense\n-------\n\n.. wtfm:end:license\n"
+        );
+        assert_eq!(render(&parse(&text, rst), rst), text);
+        assert_eq!(strip(&text, rst), "Intro\n\nLicense\n-------\n");
+    }
+}
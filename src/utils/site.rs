@@ -0,0 +1,365 @@
+use crate::utils::html;
+use pulldown_cmark::{html as cmark_html, CowStr, Event, Parser, Tag, TagEnd};
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use tera::{Context, Tera};
+
+// The layout is compiled in so the site can be built from any directory
+const LAYOUT: &str = include_str!("../../templates/site/layout.tera");
+
+/// File holding the search index, at the root of the site.
+pub const SEARCH_INDEX: &str = "search-index.json";
+
+/// A Markdown page of the site.
+#[derive(Debug)]
+pub struct Source {
+    /// Path relative to the project directory, e.g. `docs/usage.md`
+    pub path: PathBuf,
+    pub markdown: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Heading {
+    level: u8,
+    title: String,
+    id: String,
+}
+
+#[derive(Serialize)]
+struct PageContext<'a> {
+    title: &'a str,
+    url: &'a str,
+}
+
+#[derive(Serialize)]
+struct NavEntry<'a> {
+    title: &'a str,
+    url: String,
+    depth: usize,
+    current: bool,
+}
+
+#[derive(Serialize)]
+struct SearchEntry<'a> {
+    title: &'a str,
+    url: &'a str,
+    headings: Vec<&'a str>,
+    text: &'a str,
+}
+
+struct Page {
+    url: String,
+    title: String,
+    content: String,
+    toc: Vec<Heading>,
+    text: String,
+}
+
+/// Collects the README and every Markdown file under `docs/`, README first.
+pub fn collect_sources(project_folder: &Path) -> io::Result<Vec<Source>> {
+    let mut paths = Vec::new();
+    if project_folder.join("README.md").is_file() {
+        paths.push(PathBuf::from("README.md"));
+    }
+    let mut docs = Vec::new();
+    collect_markdown(project_folder, Path::new("docs"), &mut docs)?;
+    docs.sort();
+    paths.extend(docs);
+
+    paths
+        .into_iter()
+        .map(|path| {
+            Ok(Source {
+                markdown: fs::read_to_string(project_folder.join(&path))?,
+                path,
+            })
+        })
+        .collect()
+}
+
+fn collect_markdown(project_folder: &Path, dir: &Path, paths: &mut Vec<PathBuf>) -> io::Result<()> {
+    let Ok(entries) = fs::read_dir(project_folder.join(dir)) else {
+        return Ok(());
+    };
+    for entry in entries {
+        let path = dir.join(entry?.file_name());
+        if project_folder.join(&path).is_dir() {
+            collect_markdown(project_folder, &path, paths)?;
+        } else if is_markdown(&path) {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn is_markdown(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("md"))
+}
+
+/// Where a Markdown file ends up in the site: READMEs become the index of
+/// their directory, other pages keep their path with an `.html` extension.
+pub fn output_path(source: &Path) -> PathBuf {
+    let is_readme = source
+        .file_stem()
+        .is_some_and(|stem| stem.eq_ignore_ascii_case("readme"));
+    if is_readme {
+        source.with_file_name("index.html")
+    } else {
+        source.with_extension("html")
+    }
+}
+
+/// Renders every source into a page of the site and builds the search
+/// index. Returns the files to write, relative to the root of the site.
+pub fn build(site_title: &str, sources: &[Source]) -> Result<Vec<(PathBuf, String)>, tera::Error> {
+    let mut tera = Tera::default();
+    tera.add_raw_template("layout.html", LAYOUT)?;
+    // Tera's own escaping turns every `/` of the URLs into an entity
+    tera.set_escape_fn(html::escape);
+
+    let pages: Vec<Page> = sources.iter().map(render_page).collect();
+    let mut files = Vec::new();
+    for page in &pages {
+        let depth = page.url.matches('/').count();
+        let root = "../".repeat(depth);
+        let nav: Vec<NavEntry> = pages
+            .iter()
+            .map(|entry| NavEntry {
+                title: &entry.title,
+                url: format!("{}{}", root, entry.url),
+                depth: entry.url.matches('/').count(),
+                current: entry.url == page.url,
+            })
+            .collect();
+
+        let mut context = Context::new();
+        context.insert("site_title", site_title);
+        context.insert("root", &root);
+        context.insert("search_index", SEARCH_INDEX);
+        context.insert(
+            "page",
+            &PageContext {
+                title: &page.title,
+                url: &page.url,
+            },
+        );
+        context.insert("nav", &nav);
+        context.insert("toc", &page.toc);
+        context.insert("content", &page.content);
+        files.push((
+            PathBuf::from(&page.url),
+            tera.render("layout.html", &context)?,
+        ));
+    }
+
+    let index: Vec<SearchEntry> = pages
+        .iter()
+        .map(|page| SearchEntry {
+            title: &page.title,
+            url: &page.url,
+            headings: page.toc.iter().map(|h| h.title.as_str()).collect(),
+            text: &page.text,
+        })
+        .collect();
+    files.push((
+        PathBuf::from(SEARCH_INDEX),
+        serde_json::to_string(&index).map_err(tera::Error::json)?,
+    ));
+    Ok(files)
+}
+
+// Converts a page to HTML, giving every heading an anchor and pointing links
+// to other Markdown pages at their HTML output
+fn render_page(source: &Source) -> Page {
+    let mut events: Vec<Event> =
+        Parser::new_ext(&source.markdown, html::markdown_options()).collect();
+
+    let mut toc = Vec::new();
+    let mut text = String::new();
+    let mut i = 0;
+    while i < events.len() {
+        match &events[i] {
+            Event::Start(Tag::Heading { level, id, .. }) => {
+                let level = *level as u8;
+                let end = events[i..]
+                    .iter()
+                    .position(|event| matches!(event, Event::End(TagEnd::Heading(_))))
+                    .map_or(events.len(), |offset| i + offset);
+                let title = plain_text(&events[i + 1..end]);
+                let id = match id {
+                    Some(id) => id.to_string(),
+                    None => unique_slug(&title, &toc),
+                };
+                if let Event::Start(Tag::Heading { id: anchor, .. }) = &mut events[i] {
+                    *anchor = Some(CowStr::from(id.clone()));
+                }
+                toc.push(Heading { level, title, id });
+            }
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                if let Some(url) = rewrite_link(dest_url) {
+                    if let Event::Start(Tag::Link { dest_url, .. }) = &mut events[i] {
+                        *dest_url = CowStr::from(url);
+                    }
+                }
+            }
+            Event::Text(value) | Event::Code(value) => text.push_str(value),
+            Event::SoftBreak
+            | Event::HardBreak
+            | Event::End(
+                TagEnd::Paragraph
+                | TagEnd::Heading(_)
+                | TagEnd::Item
+                | TagEnd::CodeBlock
+                | TagEnd::TableCell,
+            ) => text.push(' '),
+            _ => {}
+        }
+        i += 1;
+    }
+
+    let mut content = String::new();
+    cmark_html::push_html(&mut content, events.into_iter());
+
+    let url = output_path(&source.path)
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    let title = toc
+        .iter()
+        .find(|heading| heading.level == 1)
+        .map(|heading| heading.title.clone())
+        .or_else(|| {
+            source
+                .path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+        })
+        .unwrap_or_default();
+
+    Page {
+        url,
+        title,
+        content,
+        toc,
+        text: text.split_whitespace().collect::<Vec<_>>().join(" "),
+    }
+}
+
+fn plain_text(events: &[Event]) -> String {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            Event::Text(value) | Event::Code(value) => Some(value.as_ref()),
+            _ => None,
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+// GitHub-style anchor: lowercase words joined by hyphens, numbered when taken
+fn unique_slug(title: &str, toc: &[Heading]) -> String {
+    let slug: String = title
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            c if c.is_alphanumeric() || c == '_' || c == '-' => Some(c),
+            ' ' => Some('-'),
+            _ => None,
+        })
+        .collect();
+    let slug = if slug.is_empty() {
+        "section".to_string()
+    } else {
+        slug
+    };
+
+    let mut candidate = slug.clone();
+    let mut n = 1;
+    while toc.iter().any(|heading| heading.id == candidate) {
+        candidate = format!("{}-{}", slug, n);
+        n += 1;
+    }
+    candidate
+}
+
+// Relative links to Markdown files are pointed at the generated page.
+// Absolute URLs, anchors and links to other files are left alone.
+fn rewrite_link(url: &str) -> Option<String> {
+    if url.contains("://") || url.starts_with('/') || url.starts_with("mailto:") {
+        return None;
+    }
+    let (path, fragment) = match url.split_once('#') {
+        Some((path, fragment)) => (path, Some(fragment)),
+        None => (url, None),
+    };
+    if !is_markdown(Path::new(path)) {
+        return None;
+    }
+
+    let rewritten = output_path(Path::new(path))
+        .to_string_lossy()
+        .replace('\\', "/");
+    Some(match fragment {
+        Some(fragment) => format!("{}#{}", rewritten, fragment),
+        None => rewritten,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(path: &str, markdown: &str) -> Source {
+        Source {
+            path: PathBuf::from(path),
+            markdown: markdown.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_build_site() {
+        let sources = vec![
+            source(
+                "README.md",
+                "# Demo\n\nSee the [guide](docs/guide.md#setup).\n\n## Usage\n\n## Usage\n",
+            ),
+            source(
+                "docs/guide.md",
+                "# Guide\n\n## Setup\n\nBack to the [README](../README.md), or [the web](https://example.com/a.md).\n",
+            ),
+        ];
+        let files = build("Demo", &sources).unwrap();
+        let file = |path: &str| {
+            files
+                .iter()
+                .find(|(p, _)| p == Path::new(path))
+                .map(|(_, content)| content.as_str())
+                .unwrap_or_else(|| panic!("{} not built", path))
+        };
+
+        let index = file("index.html");
+        assert!(index.contains("<a href=\"docs/guide.html#setup\">guide</a>"));
+        assert!(index.contains("<h2 id=\"usage\">Usage</h2>"));
+        assert!(index.contains("<h2 id=\"usage-1\">Usage</h2>"));
+        assert!(index.contains("<li class=\"level-2\"><a href=\"#usage-1\">Usage</a></li>"));
+        assert!(index.contains("<li class=\"depth-1\"><a href=\"docs/guide.html\">Guide</a></li>"));
+
+        let guide = file("docs/guide.html");
+        assert!(guide.contains("<a href=\"../index.html\">README</a>"));
+        assert!(guide.contains("<a href=\"https://example.com/a.md\">the web</a>"));
+        assert!(guide.contains("<li class=\"depth-0\"><a href=\"../index.html\">Demo</a></li>"));
+        assert!(guide.contains("<title>Guide - Demo</title>"));
+
+        let search: serde_json::Value = serde_json::from_str(file(SEARCH_INDEX)).unwrap();
+        assert_eq!(search[1]["url"], "docs/guide.html");
+        assert_eq!(search[1]["headings"], serde_json::json!(["Guide", "Setup"]));
+    }
+}
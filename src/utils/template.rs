@@ -1,24 +1,223 @@
 use crate::config::wizard::WizardAnswers;
+use crate::config::wtfm::{Author, Document};
+use crate::utils::badges::{self, Source};
+use crate::utils::ci::{Ecosystem, Pipeline};
+use crate::utils::format::Format;
+use crate::utils::git::Git;
+use crate::utils::{html, regions, version};
+use include_dir::{include_dir, Dir};
 use std::collections::HashMap;
+use std::path::Path;
 use tera::{Context, Tera, Value};
 
 fn newline_fn(_args: &HashMap<String, Value>) -> Result<Value, tera::Error> {
     Ok(Value::String("\n".to_string()))
 }
 
+// Underlines a title with `char`, as reStructuredText section titles need
+fn underline_filter(value: &Value, args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let title = value.as_str().unwrap_or_default();
+    let underline = args.get("char").and_then(Value::as_str).unwrap_or("=");
+    Ok(Value::String(format!(
+        "{}\n{}",
+        title,
+        underline.repeat(title.chars().count())
+    )))
+}
+
 // Template structure:
 // In the beginning we will implement a standard GitHub README.md template
 // Then we will move to more structured forms like DocBook and Diataxis
+// (DocBook is a format of its own, the Diataxis tree is built by `scaffold`)
+
+/// The templates shipped with wtfm, one directory per set, compiled in so
+/// wtfm renders from any directory.
+static BUNDLED: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/templates");
+
+/// Where a project keeps its copies of the bundled templates, one directory
+/// per set, relative to the project directory.
+pub const OVERRIDE_DIR: &str = ".wtfm/templates";
+
+/// Source of the bundled template `name` of `set`, e.g. `readme.tera` of
+/// `markdown`.
+pub fn bundled_source(set: &str, name: &str) -> Option<&'static str> {
+    BUNDLED.get_file(Path::new(set).join(name))?.contents_utf8()
+}
+
+/// The bundled templates of `set`, named after their path in the set, with
+/// those of the same name under `overrides` in their place.
+pub fn load(set: &str, overrides: Option<&Path>) -> Result<Tera, tera::Error> {
+    let mut bundled = Tera::default();
+    if let Some(dir) = BUNDLED.get_dir(set) {
+        let mut templates = Vec::new();
+        collect(dir, Path::new(set), &mut templates);
+        bundled.add_raw_templates(templates)?;
+    }
+    match overrides.filter(|dir| dir.is_dir()) {
+        Some(dir) => {
+            let mut tera = Tera::new(&format!("{}/**/*", dir.display()))?;
+            tera.extend(&bundled)?;
+            Ok(tera)
+        }
+        None => Ok(bundled),
+    }
+}
+
+// Every file under `dir` with its path relative to `root`
+fn collect(dir: &'static Dir<'static>, root: &Path, templates: &mut Vec<(String, &'static str)>) {
+    for file in dir.files() {
+        if let (Ok(name), Some(content)) = (file.path().strip_prefix(root), file.contents_utf8()) {
+            templates.push((name.to_string_lossy().replace('\\', "/"), content));
+        }
+    }
+    for dir in dir.dirs() {
+        collect(dir, root, templates);
+    }
+}
+
 pub fn generate_readme_with_template(answers: &WizardAnswers) -> String {
-    let mut tera = match Tera::new("templates/**/*") {
-        Ok(t) => t,
+    generate_readme(answers, Format::Markdown)
+}
+
+pub fn generate_readme(answers: &WizardAnswers, format: Format) -> String {
+    match render_document(
+        answers,
+        &Detected::new(answers),
+        None,
+        "readme.tera",
+        format,
+    ) {
+        Ok(rendered) => rendered,
         Err(e) => {
-            panic!("Parsing error(s): {}", e);
+            panic!("Failed to render template: {}", e);
         }
-    };
+    }
+}
+
+/// Built-in README variants with their description. The name of a variant is
+/// its entry template in every bundled set, `readme` being the default.
+pub const VARIANTS: [(&str, &str); 5] = [
+    ("readme", "Every section, the default"),
+    ("minimal", "Title, description and license only"),
+    ("library", "Installation first, for crates and packages"),
+    ("cli-tool", "Installation and usage of a command line tool"),
+    (
+        "monorepo",
+        "Project-wide sections for a repository of packages",
+    ),
+];
+
+/// Entry template of `name`, which can be given with or without `.tera`.
+pub fn template_file(name: &str) -> String {
+    if name.ends_with(".tera") {
+        name.to_string()
+    } else {
+        format!("{}.tera", name)
+    }
+}
+
+/// What is known about the project besides the answers: where its badges
+/// point, its ecosystems, the jobs of its CI pipeline and its release tags.
+#[derive(Debug, Clone, Default)]
+pub struct Detected {
+    pub badges: Source,
+    pub ecosystems: Vec<Ecosystem>,
+    pub pipeline: Option<Pipeline>,
+    pub tags: Vec<String>,
+}
+
+impl Detected {
+    pub fn new(answers: &WizardAnswers) -> Self {
+        Self {
+            badges: Source::new(answers),
+            ..Default::default()
+        }
+    }
+
+    /// Inspects the manifests and the git remote of the project in
+    /// `project_folder`.
+    pub fn detect(answers: &WizardAnswers, project_folder: &Path) -> Self {
+        let badges = Source::detect(answers, project_folder);
+        let pipeline = Pipeline::detect(
+            project_folder,
+            badges.repository.as_deref(),
+            badges.rust_version.as_deref(),
+        );
+        Self {
+            badges,
+            ecosystems: Ecosystem::detect(project_folder),
+            pipeline,
+            tags: Git::new(project_folder)
+                .info()
+                .map(|info| info.tags)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Renders `document` for the project in `project_folder`. Templates found in
+/// the template directory of the document override the bundled ones, and the
+/// badges are completed from the manifests and the git remote of the project.
+pub fn render(
+    answers: &WizardAnswers,
+    document: &Document,
+    project_folder: &Path,
+) -> Result<String, tera::Error> {
+    let overrides = document
+        .template_dir
+        .as_ref()
+        .map(|dir| project_folder.join(dir));
+    render_document(
+        answers,
+        &Detected::detect(answers, project_folder),
+        overrides.as_deref(),
+        &document.template,
+        document.format,
+    )
+}
+
+/// Renders a whole document in `format`. HTML documents are rendered from
+/// Markdown templates and turned into a standalone page.
+pub fn render_document(
+    answers: &WizardAnswers,
+    detected: &Detected,
+    overrides: Option<&Path>,
+    template: &str,
+    format: Format,
+) -> Result<String, tera::Error> {
+    let rendered = render_template(answers, detected, overrides, template, format)?;
+    if format != Format::Html {
+        return Ok(rendered);
+    }
+
+    let markdown = regions::strip(&rendered, format.markers());
+    Ok(html::standalone_page(
+        &answers.project_name,
+        &html::markdown_to_html(&markdown),
+    ))
+}
+
+/// Renders `template` from the bundled templates of `format`, with managed
+/// regions marked the way `format` expects. Templates under `overrides`
+/// replace the bundled ones with the same name, so a project can customize a
+/// single partial and inherit the rest.
+pub fn render_template(
+    answers: &WizardAnswers,
+    detected: &Detected,
+    overrides: Option<&Path>,
+    template: &str,
+    format: Format,
+) -> Result<String, tera::Error> {
+    let mut tera = load(format.template_set(), overrides)?;
 
     // Registra la funzione newline
     tera.register_function("newline", newline_fn);
+    tera.register_filter("managed", regions::managed_filter(format.markers()));
+    tera.register_filter("underline", underline_filter);
+    if format.escapes_xml() {
+        tera.autoescape_on(vec![".tera"]);
+        tera.set_escape_fn(html::escape);
+    }
 
     let mut context = Context::new();
     context.insert("project_name", &answers.project_name);
@@ -26,27 +225,67 @@ pub fn generate_readme_with_template(answers: &WizardAnswers) -> String {
     context.insert("version", &answers.version);
     context.insert("license", &answers.license);
     context.insert("setup_ci", &answers.setup_ci);
-    context.insert("authors", &answers.authors);
-
-    match tera.render("readme.tera", &context) {
-        Ok(rendered) => rendered,
-        Err(e) => {
-            panic!("Failed to render template: {}", e);
+    let pipeline = detected.pipeline.as_ref().filter(|_| answers.setup_ci);
+    context.insert("ci", &pipeline.map(Pipeline::summary));
+    let authors: Vec<String> = answers.authors.iter().map(Author::to_string).collect();
+    context.insert("authors", &authors);
+    context.insert("overview", &answers.content.overview);
+    context.insert("screenshot", &answers.content.screenshot);
+    context.insert("demo", &answers.content.demo);
+    context.insert(
+        "badges",
+        &badges::build(&answers.content.badge_list(), &detected.badges),
+    );
+    context.insert("features", &answers.content.features);
+    context.insert("getting_started", &answers.content.has_getting_started());
+    context.insert("pre_requisites", &answers.content.pre_requisites);
+    context.insert("installation", &answers.content.installation);
+    context.insert("usage", &answers.content.usage);
+    context.insert("contact", &contact_email(&answers.authors));
+    let build_commands: Vec<&str> = detected
+        .ecosystems
+        .iter()
+        .map(Ecosystem::build_command)
+        .collect();
+    context.insert("build_commands", &build_commands);
+    let mut checks: Vec<&str> = Vec::new();
+    for job in detected.pipeline.iter().flat_map(|pipeline| &pipeline.jobs) {
+        if let Some(command) = job.commands.last() {
+            if !checks.contains(&command.as_str()) {
+                checks.push(command);
+            }
         }
     }
+    context.insert("checks", &checks);
+    context.insert(
+        "supported_versions",
+        &version::supported_versions(&answers.version, &detected.tags),
+    );
+
+    tera.render(template, &context)
+}
+
+// Email of the first author who gave one
+fn contact_email(authors: &[Author]) -> Option<&str> {
+    authors
+        .iter()
+        .map(|author| author.email.as_str())
+        .find(|email| !email.is_empty())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::config::wizard::WizardAnswers;
+    use crate::config::wtfm::ReadmeContent;
     use crate::licenses::{License, LicenseCategory};
+    use crate::utils::badges::Badge;
 
     fn create_test_answers() -> WizardAnswers {
         WizardAnswers {
             project_name: "Test Project".to_string(),
             description: "A test project".to_string(),
-            version: "0.1.0".to_string(),
+            version: semver::Version::new(0, 1, 0),
             license: License::new(
                 "MIT",
                 "MIT License",
@@ -57,7 +296,8 @@ mod tests {
             ),
             setup_ci: true,
             author_quantity: 2,
-            authors: vec!["Author 1".to_string(), "Author 2".to_string()],
+            authors: vec![Author::parse("Author 1"), Author::parse("Author 2")],
+            content: ReadmeContent::default(),
         }
     }
 
@@ -76,4 +316,143 @@ mod tests {
         let readme = generate_readme_with_template(&answers);
         assert!(!readme.contains("Continuous Integration"));
     }
+
+    #[test]
+    fn test_generate_readme_with_content() {
+        let mut answers = create_test_answers();
+        answers.content = ReadmeContent {
+            overview: Some("Why it exists.".to_string()),
+            screenshot: Some("docs/screenshot.png".to_string()),
+            demo: None,
+            badge_version: true,
+            badges: vec![Badge::License, Badge::CratesIo],
+            features: vec!["Fast".to_string()],
+            pre_requisites: vec!["Rust 1.75".to_string()],
+            installation: vec!["cargo install test".to_string()],
+            usage: vec!["test --help".to_string()],
+        };
+
+        let readme = generate_readme_with_template(&answers);
+        assert!(readme.contains(
+            "# Test Project\n\n![Version](https://img.shields.io/badge/version-0.1.0-blue.svg) ![License](https://img.shields.io/badge/license-MIT-blue.svg)\n"
+        ));
+        // nothing to build the crates.io badge from without a Cargo.toml
+        assert!(!readme.contains("crates.io"));
+        assert!(readme.contains("## Overview\n\nWhy it exists."));
+        assert!(readme.contains("![Screenshot](docs/screenshot.png)"));
+        assert!(!readme.contains("![Demo]"));
+        assert!(readme.contains("## Features\n\n- Fast"));
+        assert!(readme.contains("## Getting Started"));
+        assert!(readme.contains("- Rust 1.75"));
+        assert!(readme.contains("- cargo install test"));
+        assert!(readme.contains("test --help"));
+
+        for format in [Format::Asciidoc, Format::Rst, Format::Docbook] {
+            let rendered = generate_readme(&answers, format);
+            assert!(rendered.contains("Why it exists."), "{}", format);
+            assert!(rendered.contains("cargo install test"), "{}", format);
+        }
+        let docbook = generate_readme(&answers, Format::Docbook);
+        roxmltree::Document::parse(&docbook).unwrap();
+    }
+
+    #[test]
+    fn test_generate_readme_in_every_format() {
+        let answers = create_test_answers();
+
+        let asciidoc = generate_readme(&answers, Format::Asciidoc);
+        assert!(asciidoc.contains("= Test Project"));
+        assert!(asciidoc.contains("// wtfm:start:license"));
+        assert!(asciidoc.contains("== License"));
+
+        let rst = generate_readme(&answers, Format::Rst);
+        assert!(rst.contains("Test Project\n============\n"));
+        assert!(rst.contains(".. wtfm:start:license\n\nLicense\n-------"));
+
+        let html = generate_readme(&answers, Format::Html);
+        assert!(html.contains("<h1>Test Project</h1>"));
+        assert!(html.contains("<h2>License</h2>"));
+        assert!(!html.contains("wtfm:start"));
+    }
+
+    #[test]
+    fn test_overrides_replace_single_partials() {
+        let answers = create_test_answers();
+        let overrides = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            overrides.path().join("license.tera"),
+            "\n## License\n\nAll rights reserved, {{ license.spdx_id }}.",
+        )
+        .unwrap();
+
+        let readme = render_document(
+            &answers,
+            &Detected::new(&answers),
+            Some(overrides.path()),
+            "readme.tera",
+            Format::Markdown,
+        )
+        .unwrap();
+        assert!(readme.contains("All rights reserved, MIT."));
+        assert!(readme.contains("# Test Project"));
+
+        let minimal = render_document(
+            &answers,
+            &Detected::new(&answers),
+            None,
+            &template_file("minimal"),
+            Format::Markdown,
+        )
+        .unwrap();
+        assert!(minimal.contains("<!-- wtfm:start:license -->"));
+        assert!(!minimal.contains("Authors"));
+    }
+
+    #[test]
+    fn test_generate_docbook() {
+        let mut answers = create_test_answers();
+        answers.project_name = "Fish & <Chips>".to_string();
+        answers.authors = vec![Author::parse(
+            "Jane \"JD\" Doe <jane@example.com> (https://jane.example.com)",
+        )];
+        answers.license.url = Some("https://spdx.org/licenses/MIT.html".to_string());
+
+        let article = generate_readme(&answers, Format::Docbook);
+        let document = roxmltree::Document::parse(&article).unwrap();
+        let root = document.root_element();
+        assert_eq!(root.tag_name().name(), "article");
+        assert_eq!(
+            root.tag_name().namespace(),
+            Some("http://docbook.org/ns/docbook")
+        );
+        let text_of = |name: &str| {
+            document
+                .descendants()
+                .find(|node| node.has_tag_name(name))
+                .and_then(|node| node.text())
+                .map(str::to_string)
+        };
+        assert_eq!(text_of("title").as_deref(), Some("Fish & <Chips>"));
+        assert_eq!(text_of("personname").as_deref(), Some("Jane \"JD\" Doe"));
+        assert_eq!(text_of("email").as_deref(), Some("jane@example.com"));
+        assert_eq!(text_of("releaseinfo").as_deref(), Some("0.1.0"));
+        assert!(document
+            .descendants()
+            .any(|node| node.has_tag_name("legalnotice")));
+        assert!(article.contains("<!-- wtfm:start:license -->"));
+
+        let book = render_document(
+            &answers,
+            &Detected::new(&answers),
+            None,
+            "book.tera",
+            Format::Docbook,
+        )
+        .unwrap();
+        let document = roxmltree::Document::parse(&book).unwrap();
+        assert_eq!(document.root_element().tag_name().name(), "book");
+        assert!(document
+            .descendants()
+            .any(|node| node.has_tag_name("chapter")));
+    }
 }
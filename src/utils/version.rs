@@ -0,0 +1,334 @@
+use semver::Version;
+use std::fmt;
+use toml_edit::DocumentMut;
+
+/// The kind of release a set of commits calls for, ordered by severity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Bump {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl fmt::Display for Bump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Bump::Patch => write!(f, "patch"),
+            Bump::Minor => write!(f, "minor"),
+            Bump::Major => write!(f, "major"),
+        }
+    }
+}
+
+/// Parses a version string, accepting the `v` prefix commonly used in tags.
+pub fn parse_version(raw: &str) -> Option<Version> {
+    Version::parse(raw.trim().trim_start_matches('v')).ok()
+}
+
+/// Returns the tag carrying the highest semantic version, ignoring tags that
+/// are not versions at all.
+pub fn latest_tag(tags: &[String]) -> Option<(String, Version)> {
+    tags.iter()
+        .filter_map(|tag| parse_version(tag).map(|version| (tag.clone(), version)))
+        .max_by(|(_, a), (_, b)| a.cmp(b))
+}
+
+/// Returns the latest version tag when `version` is older than it, which
+/// usually means the manifests were not bumped after a release.
+pub fn behind_latest_tag(version: &Version, tags: &[String]) -> Option<String> {
+    latest_tag(tags)
+        .filter(|(_, tagged)| version < tagged)
+        .map(|(tag, _)| tag)
+}
+
+/// Rows of a supported versions table: the release series of the latest
+/// version tag, or of `version` before the first release, is supported and
+/// anything older is not.
+pub fn supported_versions(version: &Version, tags: &[String]) -> Vec<(String, bool)> {
+    let latest = latest_tag(tags).map_or_else(|| version.clone(), |(_, tagged)| tagged);
+    let mut rows = vec![(format!("{}.{}.x", latest.major, latest.minor), true)];
+    if latest.major > 0 || latest.minor > 0 {
+        rows.push((format!("< {}.{}", latest.major, latest.minor), false));
+    }
+    rows
+}
+
+/// Classifies a commit message following the Conventional Commits rules:
+/// `BREAKING CHANGE` footers and `type!:` headers are major, `feat` is minor
+/// and `fix` is patch. Any other message does not call for a release.
+pub fn classify_commit(message: &str) -> Option<Bump> {
+    if message.contains("BREAKING CHANGE:") || message.contains("BREAKING-CHANGE:") {
+        return Some(Bump::Major);
+    }
+
+    let header = message.lines().next()?;
+    let (prefix, _) = header.split_once(':')?;
+    if prefix.ends_with('!') {
+        return Some(Bump::Major);
+    }
+
+    let commit_type = prefix.split('(').next()?.trim();
+    match commit_type {
+        "feat" => Some(Bump::Minor),
+        "fix" => Some(Bump::Patch),
+        _ => None,
+    }
+}
+
+/// Suggests the bump for a list of commit messages. Commits that don't follow
+/// the convention still count as a patch release, while no commits at all
+/// means there is nothing to release.
+pub fn suggest_bump(commits: &[String]) -> Option<Bump> {
+    if commits.is_empty() {
+        return None;
+    }
+
+    commits
+        .iter()
+        .filter_map(|message| classify_commit(message))
+        .max()
+        .or(Some(Bump::Patch))
+}
+
+/// The version after `bump`. A pre-release is released as is when it
+/// already has the level of the bump, `1.2.0-rc.1` becoming `1.2.0` on a
+/// minor or patch bump, as pre-releases come before their release.
+pub fn apply_bump(version: &Version, bump: Bump) -> Version {
+    let pre_release = !version.pre.is_empty();
+    match bump {
+        Bump::Major if pre_release && version.minor == 0 && version.patch == 0 => {
+            Version::new(version.major, 0, 0)
+        }
+        Bump::Major => Version::new(version.major + 1, 0, 0),
+        Bump::Minor if pre_release && version.patch == 0 => {
+            Version::new(version.major, version.minor, 0)
+        }
+        Bump::Minor => Version::new(version.major, version.minor + 1, 0),
+        Bump::Patch if pre_release => Version::new(version.major, version.minor, version.patch),
+        Bump::Patch => Version::new(version.major, version.minor, version.patch + 1),
+    }
+}
+
+/// Rewrites the version of a Cargo manifest keeping its formatting and
+/// comments untouched. Both `[package]` and `[workspace.package]` are
+/// updated; `None` is returned when neither declares a literal version.
+pub fn set_cargo_version(
+    content: &str,
+    version: &Version,
+) -> Result<Option<String>, toml_edit::TomlError> {
+    let mut document: DocumentMut = content.parse()?;
+    let mut updated = false;
+
+    for path in [&["package"][..], &["workspace", "package"][..]] {
+        let table = path.iter().try_fold(document.as_item_mut(), |item, key| {
+            item.as_table_like_mut()?.get_mut(key)
+        });
+        if let Some(value) = table
+            .and_then(|table| table.as_table_like_mut()?.get_mut("version"))
+            .and_then(|version| version.as_value_mut())
+        {
+            if value.is_str() {
+                let decor = value.decor().clone();
+                *value = toml_edit::Value::from(version.to_string());
+                *value.decor_mut() = decor;
+                updated = true;
+            }
+        }
+    }
+
+    Ok(updated.then(|| document.to_string()))
+}
+
+/// Rewrites the top-level `version` of a `package.json` in place, so key
+/// order, indentation and nested `version` fields are left alone.
+pub fn set_package_json_version(content: &str, version: &Version) -> Option<String> {
+    let (start, end) = find_top_level_value(content, "version")?;
+    if !content[start..].starts_with('"') {
+        return None;
+    }
+    Some(format!(
+        "{}\"{}\"{}",
+        &content[..start],
+        version,
+        &content[end..]
+    ))
+}
+
+/// Finds the byte span of the value stored under `key` in the outermost
+/// JSON object, quotes and brackets included.
+pub(crate) fn find_top_level_value(content: &str, key: &str) -> Option<(usize, usize)> {
+    let bytes = content.as_bytes();
+    let skip_whitespace = |mut i: usize| {
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        i
+    };
+
+    let mut depth = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                let end = string_end(bytes, i)?;
+                if depth == 1 {
+                    let colon = skip_whitespace(end + 1);
+                    if bytes.get(colon) == Some(&b':') && &content[i + 1..end] == key {
+                        let value = skip_whitespace(colon + 1);
+                        return Some((value, value_end(bytes, value)?));
+                    }
+                }
+                i = end;
+            }
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => depth -= 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Index just past the JSON value starting at `start`.
+fn value_end(bytes: &[u8], start: usize) -> Option<usize> {
+    match bytes.get(start)? {
+        b'"' => Some(string_end(bytes, start)? + 1),
+        b'{' | b'[' => {
+            let mut depth = 0;
+            let mut i = start;
+            while i < bytes.len() {
+                match bytes[i] {
+                    b'"' => i = string_end(bytes, i)?,
+                    b'{' | b'[' => depth += 1,
+                    b'}' | b']' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Some(i + 1);
+                        }
+                    }
+                    _ => {}
+                }
+                i += 1;
+            }
+            None
+        }
+        _ => {
+            let end = bytes[start..]
+                .iter()
+                .position(|b| matches!(b, b',' | b'}' | b']') || b.is_ascii_whitespace())
+                .map_or(bytes.len(), |len| start + len);
+            Some(end)
+        }
+    }
+}
+
+/// Index of the quote closing the JSON string opening at `start`.
+fn string_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return Some(i),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commits(messages: &[&str]) -> Vec<String> {
+        messages.iter().map(|m| m.to_string()).collect()
+    }
+
+    #[test]
+    fn test_latest_tag() {
+        let tags = commits(&["v0.9.0", "v0.10.0", "nightly", "0.2.1"]);
+        let (tag, version) = latest_tag(&tags).unwrap();
+        assert_eq!(tag, "v0.10.0");
+        assert_eq!(version, Version::new(0, 10, 0));
+    }
+
+    #[test]
+    fn test_behind_latest_tag() {
+        let tags = commits(&["v1.0.0", "v1.1.0"]);
+        assert_eq!(
+            behind_latest_tag(&Version::new(1, 0, 5), &tags).as_deref(),
+            Some("v1.1.0")
+        );
+        assert_eq!(behind_latest_tag(&Version::new(1, 1, 0), &tags), None);
+        assert_eq!(behind_latest_tag(&Version::new(0, 1, 0), &[]), None);
+    }
+
+    #[test]
+    fn test_supported_versions() {
+        let tags = commits(&["v1.1.0", "v1.2.3", "nightly"]);
+        assert_eq!(
+            supported_versions(&Version::new(1, 3, 0), &tags),
+            vec![("1.2.x".to_string(), true), ("< 1.2".to_string(), false)]
+        );
+        assert_eq!(
+            supported_versions(&Version::new(0, 0, 1), &[]),
+            vec![("0.0.x".to_string(), true)]
+        );
+    }
+
+    #[test]
+    fn test_suggest_bump() {
+        assert_eq!(suggest_bump(&[]), None);
+        assert_eq!(suggest_bump(&commits(&["chore: tidy"])), Some(Bump::Patch));
+        assert_eq!(
+            suggest_bump(&commits(&["fix: typo", "feat(cli): add flag"])),
+            Some(Bump::Minor)
+        );
+        assert_eq!(
+            suggest_bump(&commits(&["feat!: drop config v1", "fix: typo"])),
+            Some(Bump::Major)
+        );
+        assert_eq!(
+            suggest_bump(&commits(&["refactor: x\n\nBREAKING CHANGE: gone"])),
+            Some(Bump::Major)
+        );
+    }
+
+    #[test]
+    fn test_apply_bump() {
+        let version = Version::parse("1.2.3").unwrap();
+        assert_eq!(apply_bump(&version, Bump::Major).to_string(), "2.0.0");
+        assert_eq!(apply_bump(&version, Bump::Minor).to_string(), "1.3.0");
+        assert_eq!(apply_bump(&version, Bump::Patch).to_string(), "1.2.4");
+
+        let rc = Version::parse("1.2.0-rc.1").unwrap();
+        assert_eq!(apply_bump(&rc, Bump::Patch).to_string(), "1.2.0");
+        assert_eq!(apply_bump(&rc, Bump::Minor).to_string(), "1.2.0");
+        assert_eq!(apply_bump(&rc, Bump::Major).to_string(), "2.0.0");
+        let beta = Version::parse("2.0.0-beta").unwrap();
+        assert_eq!(apply_bump(&beta, Bump::Major).to_string(), "2.0.0");
+        let alpha = Version::parse("1.2.3-alpha").unwrap();
+        assert_eq!(apply_bump(&alpha, Bump::Minor).to_string(), "1.3.0");
+    }
+
+    #[test]
+    fn test_set_cargo_version_preserves_format() {
+        let manifest = "[package]\nname = \"demo\"\nversion   = \"0.1.0\" # keep me\n\n[dependencies]\nserde = { version = \"1.0\" }\n";
+        let updated = set_cargo_version(manifest, &Version::new(0, 2, 0))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            updated,
+            "[package]\nname = \"demo\"\nversion   = \"0.2.0\" # keep me\n\n[dependencies]\nserde = { version = \"1.0\" }\n"
+        );
+    }
+
+    #[test]
+    fn test_set_package_json_version() {
+        let package = "{\n  \"name\": \"demo\",\n  \"engines\": { \"version\": \"x\" },\n  \"version\" : \"1.0.0\"\n}\n";
+        let updated = set_package_json_version(package, &Version::new(1, 1, 0)).unwrap();
+        assert_eq!(
+            updated,
+            "{\n  \"name\": \"demo\",\n  \"engines\": { \"version\": \"x\" },\n  \"version\" : \"1.1.0\"\n}\n"
+        );
+    }
+}
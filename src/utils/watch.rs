@@ -0,0 +1,121 @@
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher as _};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+/// How long the files must stay untouched before a burst of changes is over.
+pub const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Watches a set of files and directories for changes.
+///
+/// Files are watched through their parent directory so editors replacing a
+/// file on save are followed, and files created later are noticed too.
+pub struct Watcher {
+    // dropping the watcher stops the notifications
+    _watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+    files: Vec<PathBuf>,
+    dirs: Vec<PathBuf>,
+}
+
+impl Watcher {
+    /// Watches `files` and, recursively, `dirs`. Directories that don't exist
+    /// are skipped.
+    pub fn new(files: &[PathBuf], dirs: &[PathBuf]) -> notify::Result<Self> {
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+
+        let files: Vec<PathBuf> = files.iter().filter_map(|file| absolute(file)).collect();
+        let dirs: Vec<PathBuf> = dirs
+            .iter()
+            .filter_map(|dir| dir.canonicalize().ok())
+            .filter(|dir| dir.is_dir())
+            .collect();
+
+        let mut parents: Vec<&Path> = files.iter().filter_map(|file| file.parent()).collect();
+        parents.sort();
+        parents.dedup();
+        for parent in parents {
+            watcher.watch(parent, RecursiveMode::NonRecursive)?;
+        }
+        for dir in &dirs {
+            watcher.watch(dir, RecursiveMode::Recursive)?;
+        }
+
+        Ok(Self {
+            _watcher: watcher,
+            events,
+            files,
+            dirs,
+        })
+    }
+
+    /// Blocks until something watched changes, then waits for the burst of
+    /// changes to settle. Returns the changed paths, or `None` once the
+    /// watcher has stopped.
+    pub fn wait(&self) -> Option<Vec<PathBuf>> {
+        let mut changed = Vec::new();
+        while changed.is_empty() {
+            let event = self.events.recv().ok()?;
+            self.collect(event, &mut changed);
+        }
+
+        loop {
+            match self.events.recv_timeout(DEBOUNCE) {
+                Ok(event) => self.collect(event, &mut changed),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return None,
+            }
+        }
+        changed.sort();
+        changed.dedup();
+        Some(changed)
+    }
+
+    fn collect(&self, event: notify::Result<Event>, changed: &mut Vec<PathBuf>) {
+        let Ok(event) = event else {
+            return;
+        };
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        changed.extend(event.paths.into_iter().filter(|path| self.is_watched(path)));
+    }
+
+    fn is_watched(&self, path: &Path) -> bool {
+        self.files.iter().any(|file| file == path)
+            || self.dirs.iter().any(|dir| path.starts_with(dir))
+    }
+}
+
+// Canonical path of a file that may not exist yet
+fn absolute(file: &Path) -> Option<PathBuf> {
+    let parent = match file.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    Some(parent.canonicalize().ok()?.join(file.file_name()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_wait_reports_watched_paths_only() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        fs::create_dir_all(root.join("docs")).unwrap();
+        let watcher = Watcher::new(&[root.join(".wtfm.json")], &[root.join("docs")]).unwrap();
+
+        fs::write(root.join("unrelated.txt"), "x").unwrap();
+        fs::write(root.join(".wtfm.json"), "{}").unwrap();
+        fs::write(root.join("docs/index.md"), "# Docs").unwrap();
+
+        let changed = watcher.wait().unwrap();
+        assert!(changed.contains(&root.join(".wtfm.json")));
+        assert!(!changed.contains(&root.join("unrelated.txt")));
+    }
+}
@@ -0,0 +1,49 @@
+use std::fs;
+use std::process::Command;
+
+const PROJECT: &str = r#"{
+  "schema_version": 2,
+  "project_name": "Demo",
+  "description": "A demo",
+  "version": "0.1.0",
+  "license": "MIT",
+  "setup_ci": false,
+  "author_quantity": 0,
+  "authors": [],
+  "git_info": null,
+  "cargo_info": null
+}"#;
+
+#[test]
+fn test_config_set_leaves_out_user_defaults() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let config_home = temp_dir.path().join("config");
+    fs::create_dir_all(config_home.join("wtfm")).unwrap();
+    fs::write(
+        config_home.join("wtfm").join("config.json"),
+        r#"{"badges":["ci"],"template":"minimal"}"#,
+    )
+    .unwrap();
+    let project_folder = temp_dir.path().join("project");
+    fs::create_dir_all(&project_folder).unwrap();
+    let path = project_folder.join(".wtfm.json");
+    fs::write(&path, PROJECT).unwrap();
+
+    let status = Command::new(env!("CARGO_BIN_EXE_wtfm"))
+        .args([
+            "config",
+            "set",
+            "description",
+            "changed",
+            "--project-folder",
+        ])
+        .arg(&project_folder)
+        .env("XDG_CONFIG_HOME", &config_home)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        PROJECT.replace("\"A demo\"", "\"changed\"")
+    );
+}
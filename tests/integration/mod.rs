@@ -1 +1,2 @@
+mod config_test;
 mod template_test;